@@ -0,0 +1,120 @@
+/* rlunar C API, generated by cbindgen from src/ffi.rs; do not edit. */
+
+#ifndef RLUNAR_H
+#define RLUNAR_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+// Result code of every rlunar_* function.
+typedef enum RlunarStatus {
+  RLUNAR_STATUS_OK = 0,
+  RLUNAR_STATUS_NULL_POINTER = 1,
+  // Day, month, hour or minute out of range, or a leap month the year does not have.
+  RLUNAR_STATUS_INVALID_DATE = 2,
+  // Year outside 1900..=2100.
+  RLUNAR_STATUS_OUT_OF_RANGE = 3,
+  RLUNAR_STATUS_INVALID_TIME_ZONE = 4,
+  RLUNAR_STATUS_INVALID_LOCALE = 5,
+  // Stem, branch or month number out of range.
+  RLUNAR_STATUS_INVALID_INDEX = 6,
+  // The name does not fit; nothing is written beyond the buffer.
+  RLUNAR_STATUS_BUFFER_TOO_SMALL = 7,
+} RlunarStatus;
+
+// Gregorian date and time in the local time of the time zone given alongside.
+typedef struct RlunarGregorian {
+  int32_t year;
+  uint32_t month;
+  uint32_t day;
+  uint32_t hour;
+  uint32_t minute;
+} RlunarGregorian;
+
+typedef struct RlunarLunisolar {
+  int32_t year;
+  uint32_t month;
+  uint32_t day;
+  bool leap;
+  uint32_t hour;
+  uint32_t minute;
+} RlunarLunisolar;
+
+// Stem 0 (Giáp) to 9 (Quý) and branch 0 (Tý) to 11 (Hợi).
+typedef struct RlunarPillar {
+  uint8_t stem;
+  uint8_t branch;
+} RlunarPillar;
+
+typedef struct RlunarPillars {
+  struct RlunarPillar year;
+  struct RlunarPillar month;
+  struct RlunarPillar day;
+  struct RlunarPillar hour;
+} RlunarPillars;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+// Lunisolar date of a Gregorian date and time, time_zone in hours east of UTC (7 for Vietnam).
+//
+// # Safety
+// `gregorian` must be readable and `out` writable, or null.
+enum RlunarStatus rlunar_gregorian_to_lunisolar(const struct RlunarGregorian *gregorian,
+                                                double time_zone,
+                                                struct RlunarLunisolar *out);
+
+// Gregorian date of a lunisolar date, the time of day carried over unchanged.
+//
+// # Safety
+// `lunisolar` must be readable and `out` writable, or null.
+enum RlunarStatus rlunar_lunisolar_to_gregorian(const struct RlunarLunisolar *lunisolar,
+                                                double time_zone,
+                                                struct RlunarGregorian *out);
+
+// Can Chi of the year, month, day and hour of a Gregorian date and time.
+//
+// # Safety
+// `gregorian` must be readable and `out` writable, or null.
+enum RlunarStatus rlunar_pillars(const struct RlunarGregorian *gregorian,
+                                 double time_zone,
+                                 struct RlunarPillars *out);
+
+// Name of stem 0..=9 in a locale tag such as "vi", "zh-Hans" or "en", UTF-8 and NUL-terminated.
+//
+// # Safety
+// `locale` must be a NUL-terminated string and `buffer` hold `buffer_len` bytes, or be null.
+enum RlunarStatus rlunar_stem_name(uint8_t stem,
+                                   const char *locale,
+                                   char *buffer,
+                                   size_t buffer_len);
+
+// Name of branch 0..=11, see rlunar_stem_name.
+//
+// # Safety
+// `locale` must be a NUL-terminated string and `buffer` hold `buffer_len` bytes, or be null.
+enum RlunarStatus rlunar_branch_name(uint8_t branch,
+                                     const char *locale,
+                                     char *buffer,
+                                     size_t buffer_len);
+
+// Name of lunisolar month 1..=12, such as "Tháng Giêng" or "闰二月", see rlunar_stem_name.
+//
+// # Safety
+// `locale` must be a NUL-terminated string and `buffer` hold `buffer_len` bytes, or be null.
+enum RlunarStatus rlunar_month_name(uint32_t month,
+                                    bool leap,
+                                    const char *locale,
+                                    char *buffer,
+                                    size_t buffer_len);
+
+// Static English description of a status, never null.
+const char *rlunar_status_message(enum RlunarStatus status);
+
+#ifdef __cplusplus
+}  // extern "C"
+#endif  // __cplusplus
+
+#endif  /* RLUNAR_H */
@@ -0,0 +1,64 @@
+"""Vietnamese and East Asian lunisolar calendar, Can Chi and solar terms, backed by the rlunar crate."""
+from ._rlunar import (
+    CanChi,
+    LunarDateTime,
+    LunisolarDate,
+    SolarTerm,
+    epoch_days_to_lunisolar,
+    gregorian_to_lunisolar,
+    leap_month,
+    lunisolar_to_gregorian,
+    solar_terms,
+)
+
+__all__ = [
+    "CanChi",
+    "LunarDateTime",
+    "LunisolarDate",
+    "SolarTerm",
+    "gregorian_to_lunisolar",
+    "leap_month",
+    "lunisolar_to_gregorian",
+    "solar_terms",
+    "to_lunisolar",
+]
+
+_COLUMNS = ("year", "month", "day", "leap")
+
+
+def _epoch_days(values):
+    """Days since 1970-01-01 of each local date in values, as an int64 buffer.
+
+    Arrays and Series are cast by numpy and handed over without building Python ints.
+    """
+    try:
+        import numpy as np
+    except ImportError:
+        np = None
+    if np is not None and hasattr(values, "__array__"):
+        dates = np.asarray(values, dtype="datetime64[D]")
+        if np.isnat(dates).any():
+            raise ValueError("cannot convert NaT to a lunisolar date")
+        return np.ascontiguousarray(dates.view("int64").ravel())
+    import array
+    import datetime
+    epoch = datetime.date(1970, 1, 1).toordinal()
+    return array.array("q", [(value.date() if isinstance(value, datetime.datetime) else value).toordinal() - epoch
+                             for value in values])
+
+
+def to_lunisolar(values, time_zone=7.0):
+    """Lunisolar year, month, day and leap flag of many Gregorian dates at once.
+
+    A pandas Series (naive, local dates) gives a DataFrame with the same index, a numpy array
+    gives a dict of numpy arrays, and any other iterable of dates gives a dict of lists.
+    """
+    years, months, days, leaps = epoch_days_to_lunisolar(_epoch_days(values), time_zone)
+    columns = dict(zip(_COLUMNS, (years, months, days, leaps)))
+    if type(values).__name__ == "Series" and hasattr(values, "index"):
+        import pandas as pd
+        return pd.DataFrame(columns, index=values.index)
+    if hasattr(values, "__array__"):
+        import numpy as np
+        return {name: np.asarray(column) for name, column in columns.items()}
+    return columns
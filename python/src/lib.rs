@@ -0,0 +1,364 @@
+// rlunar._rlunar, wrapped by the pure Python package in rlunar/ which adds the numpy and pandas helpers
+use chrono::{DateTime, Datelike, FixedOffset, NaiveDate, NaiveDateTime, TimeZone};
+use pyo3::buffer::PyBuffer;
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rlunar::{EarthlyBranch, HeavenlyStem, Locale, LunarDateTimeObject, LunarFormat, NaiveLunarDateTime, NaiveLunisolarDateTime};
+
+fn value_error(error: anyhow::Error) -> PyErr {
+    PyValueError::new_err(error.to_string())
+}
+
+fn locale(tag: &str) -> PyResult<Locale> {
+    Locale::from_tag(tag).ok_or_else(|| PyValueError::new_err(format!("unknown locale {}", tag)))
+}
+
+// NaN would otherwise round to an offset of 0 and silently compute in UTC
+fn offset(time_zone: f64) -> PyResult<FixedOffset> {
+    FixedOffset::east_opt((time_zone * 3600.0).round() as i32)
+        .filter(|_| time_zone.is_finite())
+        .ok_or_else(|| PyValueError::new_err(format!("invalid time zone {}", time_zone)))
+}
+
+// Aware datetimes keep their own offset, naive datetimes and dates are read in time_zone
+fn datetime(value: &Bound<'_, PyAny>, time_zone: f64) -> PyResult<DateTime<FixedOffset>> {
+    if let Ok(datetime) = value.extract::<DateTime<FixedOffset>>() {
+        return Ok(datetime);
+    }
+    let naive = match value.extract::<NaiveDateTime>() {
+        Ok(naive) => naive,
+        Err(_) => value.extract::<NaiveDate>()?.and_hms_opt(0, 0, 0).unwrap(),
+    };
+    offset(time_zone)?.from_local_datetime(&naive).single()
+        .ok_or_else(|| PyValueError::new_err(format!("invalid local time {}", naive)))
+}
+
+/// One of the sixty stem and branch pairs, stem 0 (Giáp) to 9 (Quý) and branch 0 (Tý) to 11 (Hợi).
+#[pyclass(frozen, eq, hash, module = "rlunar")]
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct CanChi {
+    inner: LunarFormat,
+}
+
+#[pymethods]
+impl CanChi {
+    #[new]
+    fn new(stem: u8, branch: u8) -> PyResult<CanChi> {
+        let stem = HeavenlyStem::try_from(stem).map_err(value_error)?;
+        let branch = EarthlyBranch::try_from(branch).map_err(value_error)?;
+        if stem.to_numeric() % 2 != branch.to_numeric() % 2 {
+            return Err(PyValueError::new_err("stem and branch must have the same parity"));
+        }
+        Ok(CanChi { inner: LunarFormat::new(stem, branch) })
+    }
+
+    #[getter]
+    fn stem(&self) -> u32 {
+        self.inner.stem.to_numeric()
+    }
+
+    #[getter]
+    fn branch(&self) -> u32 {
+        self.inner.branch.to_numeric()
+    }
+
+    /// Position in the sexagenary cycle, Giáp Tý being 0.
+    #[getter]
+    fn index(&self) -> u32 {
+        self.inner.sexagenary_index()
+    }
+
+    #[pyo3(signature = (locale = "vi"))]
+    fn name(&self, locale: &str) -> PyResult<String> {
+        let locale = self::locale(locale)?;
+        Ok(match locale {
+            Locale::Vi | Locale::En => format!("{} {}", self.inner.stem.localized_name(&locale), self.inner.branch.localized_name(&locale)),
+            _ => format!("{}{}", self.inner.stem.localized_name(&locale), self.inner.branch.localized_name(&locale)),
+        })
+    }
+
+    #[pyo3(signature = (locale = "vi"))]
+    fn zodiac(&self, locale: &str) -> PyResult<&'static str> {
+        Ok(self.inner.branch.as_zodiac().localized_name(&self::locale(locale)?))
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("CanChi({}, {})", self.stem(), self.branch())
+    }
+}
+
+/// A date and time of the lunisolar calendar; leap marks the intercalary month.
+/// The date has to exist in the calendar of time_zone, hours east of UTC.
+#[pyclass(frozen, eq, ord, hash, module = "rlunar")]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct LunisolarDate {
+    inner: NaiveLunisolarDateTime,
+}
+
+#[pymethods]
+impl LunisolarDate {
+    #[new]
+    #[pyo3(signature = (year, month, day, leap = false, hour = 0, minute = 0, time_zone = 7.0))]
+    fn new(year: u32, month: u32, day: u32, leap: bool, hour: u32, minute: u32, time_zone: f64) -> PyResult<LunisolarDate> {
+        offset(time_zone)?;
+        let year_i32 = i32::try_from(year).map_err(|_| PyValueError::new_err(format!("invalid lunisolar year {}", year)))?;
+        let days_in_month = NaiveLunisolarDateTime::days_in_lunisolar_month(month, year_i32, leap, time_zone).map_err(value_error)?;
+        if !(1..=days_in_month).contains(&day) || hour > 23 || minute > 59 {
+            return Err(PyValueError::new_err("invalid lunisolar date"));
+        }
+        Ok(LunisolarDate {
+            inner: NaiveLunisolarDateTime {
+                hour_lunisolar: hour,
+                minute_lunisolar: minute,
+                day_lunisolar: day,
+                month_lunisolar: month,
+                leap_lunisolar: leap,
+                year_lunisolar: year,
+            },
+        })
+    }
+
+    #[getter]
+    fn year(&self) -> u32 {
+        self.inner.year_lunisolar
+    }
+
+    #[getter]
+    fn month(&self) -> u32 {
+        self.inner.month_lunisolar
+    }
+
+    #[getter]
+    fn day(&self) -> u32 {
+        self.inner.day_lunisolar
+    }
+
+    #[getter]
+    fn leap(&self) -> bool {
+        self.inner.leap_lunisolar
+    }
+
+    #[getter]
+    fn hour(&self) -> u32 {
+        self.inner.hour_lunisolar
+    }
+
+    #[getter]
+    fn minute(&self) -> u32 {
+        self.inner.minute_lunisolar
+    }
+
+    /// The datetime.datetime this lunisolar date falls on, aware in time_zone.
+    #[pyo3(signature = (time_zone = 7.0))]
+    fn to_datetime(&self, time_zone: f64) -> PyResult<DateTime<FixedOffset>> {
+        let date = lunisolar_to_gregorian(self.year() as i32, self.month(), self.day(), self.leap(), time_zone)?;
+        let naive = date.and_hms_opt(self.hour(), self.minute(), 0).unwrap();
+        Ok(offset(time_zone)?.from_local_datetime(&naive).unwrap())
+    }
+
+    #[pyo3(signature = (locale = "vi"))]
+    fn month_name(&self, locale: &str) -> PyResult<String> {
+        Ok(self.inner.localized_month_name(&self::locale(locale)?))
+    }
+
+    fn format(&self, format: &str) -> PyResult<String> {
+        self.inner.format(format).map_err(value_error)
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("LunisolarDate({}, {}, {}, leap={}, hour={}, minute={})",
+                self.year(), self.month(), self.day(), if self.leap() { "True" } else { "False" }, self.hour(), self.minute())
+    }
+}
+
+/// LunarDateTimeObject: a Gregorian instant with its lunisolar date and Can Chi pillars.
+#[pyclass(frozen, eq, ord, hash, module = "rlunar")]
+#[derive(Clone, PartialEq, Eq, PartialOrd, Ord, Hash)]
+struct LunarDateTime {
+    inner: LunarDateTimeObject,
+}
+
+#[pymethods]
+impl LunarDateTime {
+    /// From a datetime.datetime or datetime.date; naive values are read in time_zone, hours east of UTC.
+    #[new]
+    #[pyo3(signature = (value, time_zone = 7.0))]
+    fn new(value: &Bound<'_, PyAny>, time_zone: f64) -> PyResult<LunarDateTime> {
+        let gregorian_datetime = datetime(value, time_zone)?;
+        Ok(LunarDateTime {
+            inner: LunarDateTimeObject {
+                lunisolar_datetime: NaiveLunisolarDateTime::new_from_datetime(gregorian_datetime).map_err(value_error)?,
+                lunar_datetime: NaiveLunarDateTime::new_from_datetime(gregorian_datetime).map_err(value_error)?,
+                gregorian_datetime,
+            },
+        })
+    }
+
+    #[getter]
+    fn gregorian(&self) -> DateTime<FixedOffset> {
+        self.inner.gregorian_datetime
+    }
+
+    #[getter]
+    fn lunisolar(&self) -> LunisolarDate {
+        LunisolarDate { inner: self.inner.lunisolar_datetime }
+    }
+
+    #[getter]
+    fn year_pillar(&self) -> CanChi {
+        CanChi { inner: self.inner.lunar_datetime.year_lunar }
+    }
+
+    #[getter]
+    fn month_pillar(&self) -> CanChi {
+        CanChi { inner: self.inner.lunar_datetime.month_lunar }
+    }
+
+    #[getter]
+    fn day_pillar(&self) -> CanChi {
+        CanChi { inner: self.inner.lunar_datetime.date_lunar }
+    }
+
+    #[getter]
+    fn hour_pillar(&self) -> CanChi {
+        CanChi { inner: self.inner.lunar_datetime.hour_lunar }
+    }
+
+    /// Term the sun is in at this instant.
+    #[getter]
+    fn solar_term(&self) -> SolarTerm {
+        SolarTerm { inner: rlunar::SolarTerm::from_datetime(self.inner.gregorian_datetime) }
+    }
+
+    fn format(&self, format: &str) -> PyResult<String> {
+        self.inner.format(format).map_err(value_error)
+    }
+
+    fn __str__(&self) -> String {
+        self.inner.to_string()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("LunarDateTime({})", self.inner.gregorian_datetime.to_rfc3339())
+    }
+}
+
+/// One of the 24 solar terms, index 0 being Xuân phân at 0° of solar longitude.
+#[pyclass(frozen, eq, hash, module = "rlunar")]
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct SolarTerm {
+    inner: rlunar::SolarTerm,
+}
+
+#[pymethods]
+impl SolarTerm {
+    #[getter]
+    fn index(&self) -> u32 {
+        self.inner.to_numeric()
+    }
+
+    /// Solar longitude in degrees at which the term begins.
+    #[getter]
+    fn longitude(&self) -> u32 {
+        self.inner.longitude()
+    }
+
+    #[pyo3(signature = (locale = "vi"))]
+    fn name(&self, locale: &str) -> PyResult<&'static str> {
+        Ok(self.inner.localized_name(&self::locale(locale)?))
+    }
+
+    fn __str__(&self) -> &'static str {
+        self.inner.localized_name(&Locale::Vi)
+    }
+
+    fn __repr__(&self) -> String {
+        format!("SolarTerm({})", self.index())
+    }
+}
+
+/// Lunisolar date of a datetime.datetime or datetime.date.
+#[pyfunction]
+#[pyo3(signature = (value, time_zone = 7.0))]
+fn gregorian_to_lunisolar(value: &Bound<'_, PyAny>, time_zone: f64) -> PyResult<LunisolarDate> {
+    let inner = NaiveLunisolarDateTime::new_from_datetime(datetime(value, time_zone)?).map_err(value_error)?;
+    Ok(LunisolarDate { inner })
+}
+
+/// datetime.date of a lunisolar date.
+#[pyfunction]
+#[pyo3(signature = (year, month, day, leap = false, time_zone = 7.0))]
+fn lunisolar_to_gregorian(year: i32, month: u32, day: u32, leap: bool, time_zone: f64) -> PyResult<NaiveDate> {
+    offset(time_zone)?;
+    NaiveLunisolarDateTime::lunisolar_to_gregorian(day, month, year, leap, time_zone).map_err(value_error)
+}
+
+/// The intercalary month of a lunisolar year, or None.
+#[pyfunction]
+#[pyo3(signature = (year, time_zone = 7.0))]
+fn leap_month(year: i32, time_zone: f64) -> PyResult<Option<u32>> {
+    offset(time_zone)?;
+    // Validates the year range of the calendar tables
+    NaiveLunisolarDateTime::days_in_lunisolar_month(1, year, false, time_zone).map_err(value_error)?;
+    Ok(NaiveLunisolarDateTime::leap_month_of_year(year, time_zone))
+}
+
+/// (term, datetime.date) for each term beginning in a Gregorian year.
+#[pyfunction]
+#[pyo3(signature = (year, time_zone = 7.0))]
+fn solar_terms(year: i32, time_zone: f64) -> PyResult<Vec<(SolarTerm, NaiveDate)>> {
+    offset(time_zone)?;
+    Ok(rlunar::SolarTerm::dates_in_year(year, time_zone).map_err(value_error)?.into_iter()
+        .map(|(inner, date)| (SolarTerm { inner }, date))
+        .collect())
+}
+
+// Columns of a batch conversion: years, months, days and leap flags
+type LunisolarColumns = (Vec<u32>, Vec<u32>, Vec<u32>, Vec<bool>);
+
+/// Batch conversion behind rlunar.to_lunisolar: days since 1970-01-01 to (years, months, days, leaps).
+/// days is any int64 buffer, such as a numpy array or an array.array("q"), copied once without Python objects.
+#[pyfunction]
+#[pyo3(signature = (days, time_zone = 7.0))]
+fn epoch_days_to_lunisolar(py: Python<'_>, days: PyBuffer<i64>, time_zone: f64) -> PyResult<LunisolarColumns> {
+    let offset = offset(time_zone)?;
+    let days = days.to_vec(py)?;
+    let epoch = NaiveDate::from_ymd_opt(1970, 1, 1).unwrap();
+    py.allow_threads(|| {
+        let mut result: LunisolarColumns = (Vec::with_capacity(days.len()), Vec::with_capacity(days.len()),
+                                            Vec::with_capacity(days.len()), Vec::with_capacity(days.len()));
+        for day in days {
+            let date = chrono::TimeDelta::try_days(day).and_then(|delta| epoch.checked_add_signed(delta))
+                .ok_or_else(|| PyValueError::new_err(format!("day {} out of range", day)))?;
+            let midnight = offset.with_ymd_and_hms(date.year(), date.month(), date.day(), 0, 0, 0).unwrap();
+            let lunisolar = NaiveLunisolarDateTime::new_from_datetime(midnight).map_err(value_error)?;
+            result.0.push(lunisolar.year_lunisolar);
+            result.1.push(lunisolar.month_lunisolar);
+            result.2.push(lunisolar.day_lunisolar);
+            result.3.push(lunisolar.leap_lunisolar);
+        }
+        Ok(result)
+    })
+}
+
+#[pymodule]
+fn _rlunar(module: &Bound<'_, PyModule>) -> PyResult<()> {
+    module.add_class::<CanChi>()?;
+    module.add_class::<LunisolarDate>()?;
+    module.add_class::<LunarDateTime>()?;
+    module.add_class::<SolarTerm>()?;
+    module.add_function(wrap_pyfunction!(gregorian_to_lunisolar, module)?)?;
+    module.add_function(wrap_pyfunction!(lunisolar_to_gregorian, module)?)?;
+    module.add_function(wrap_pyfunction!(leap_month, module)?)?;
+    module.add_function(wrap_pyfunction!(solar_terms, module)?)?;
+    module.add_function(wrap_pyfunction!(epoch_days_to_lunisolar, module)?)?;
+    Ok(())
+}
@@ -0,0 +1,99 @@
+import array
+import datetime
+
+import pytest
+
+import rlunar
+
+ICT = datetime.timezone(datetime.timedelta(hours=7))
+
+
+def test_lunar_datetime_from_datetime():
+    value = rlunar.LunarDateTime(datetime.datetime(2024, 2, 10, 10, 30))
+    assert value.gregorian == datetime.datetime(2024, 2, 10, 10, 30, tzinfo=ICT)
+    assert (value.lunisolar.year, value.lunisolar.month, value.lunisolar.day) == (2024, 1, 1)
+    assert str(value.year_pillar) == "Giáp Thìn"
+    assert value.year_pillar.name("zh-Hans") == "甲辰"
+    assert value.year_pillar.zodiac("en") == "Dragon"
+    assert value.hour_pillar == rlunar.CanChi(5, 5)
+
+
+def test_aware_datetime_keeps_its_offset():
+    aware = rlunar.LunarDateTime(datetime.datetime(2024, 2, 9, 20, 0, tzinfo=datetime.timezone.utc))
+    assert aware.gregorian.utcoffset() == datetime.timedelta(0)
+    assert aware == rlunar.LunarDateTime(datetime.datetime(2024, 2, 9, 20, 0), time_zone=0)
+    # The same instant is already Tết in Hà Nội
+    assert rlunar.LunarDateTime(aware.gregorian.astimezone(ICT)).lunisolar.day == 1
+    assert rlunar.gregorian_to_lunisolar(datetime.date(2024, 2, 10)).day == 1
+
+
+def test_round_trip_through_leap_month():
+    lunisolar = rlunar.gregorian_to_lunisolar(datetime.date(2023, 3, 31))
+    assert lunisolar == rlunar.LunisolarDate(2023, 2, 10, leap=True)
+    assert lunisolar.month_name() == "Tháng Hai nhuận"
+    assert rlunar.lunisolar_to_gregorian(2023, 2, 10, leap=True) == datetime.date(2023, 3, 31)
+    assert lunisolar.to_datetime() == datetime.datetime(2023, 3, 31, tzinfo=ICT)
+    assert rlunar.leap_month(2023) == 2
+    assert rlunar.leap_month(2024) is None
+
+
+def test_errors():
+    with pytest.raises(ValueError):
+        rlunar.lunisolar_to_gregorian(2024, 2, 10, leap=True)
+    with pytest.raises(ValueError):
+        rlunar.LunarDateTime(datetime.datetime(1800, 1, 1))
+    with pytest.raises(ValueError):
+        rlunar.CanChi(0, 1)
+    with pytest.raises(ValueError):
+        rlunar.LunarDateTime(datetime.datetime(2024, 2, 10), time_zone=float("nan"))
+    with pytest.raises(ValueError):
+        rlunar.leap_month(2023, time_zone=float("nan"))
+    with pytest.raises(ValueError):
+        rlunar.leap_month(3000)
+    with pytest.raises(ValueError):
+        rlunar.solar_terms(1)
+    with pytest.raises(ValueError):
+        rlunar.solar_terms(300000)
+    with pytest.raises(ValueError):
+        rlunar.epoch_days_to_lunisolar(array.array("q", [2 ** 62]))
+
+
+def test_lunisolar_date_must_exist():
+    assert rlunar.LunisolarDate(2023, 12, 30).day == 30
+    with pytest.raises(ValueError):
+        rlunar.LunisolarDate(2024, 12, 30)
+    with pytest.raises(ValueError):
+        rlunar.LunisolarDate(2024, 2, 1, leap=True)
+    with pytest.raises(ValueError):
+        rlunar.LunisolarDate(3000, 1, 1)
+
+
+def test_solar_terms():
+    terms = rlunar.solar_terms(2024)
+    assert len(terms) == 24
+    term, date = terms[2]
+    assert (term.name(), term.longitude, date) == ("Lập xuân", 315, datetime.date(2024, 2, 4))
+    assert rlunar.LunarDateTime(datetime.date(2024, 2, 5)).solar_term == term
+
+
+def test_to_lunisolar_list():
+    columns = rlunar.to_lunisolar([datetime.date(2024, 2, 10), datetime.datetime(2023, 3, 31, 12)])
+    assert columns == {"year": [2024, 2023], "month": [1, 2], "day": [1, 10], "leap": [False, True]}
+    with pytest.raises(TypeError):
+        rlunar.epoch_days_to_lunisolar([19763])
+
+
+def test_to_lunisolar_numpy():
+    np = pytest.importorskip("numpy")
+    columns = rlunar.to_lunisolar(np.array(["2024-02-10", "2023-03-31"], dtype="datetime64[D]"))
+    assert columns["day"].tolist() == [1, 10]
+    assert columns["leap"].tolist() == [False, True]
+
+
+def test_to_lunisolar_pandas():
+    pd = pytest.importorskip("pandas")
+    series = pd.Series(pd.to_datetime(["2024-02-10", "2023-03-31"]), index=["tet", "leap"])
+    frame = rlunar.to_lunisolar(series)
+    assert list(frame.columns) == ["year", "month", "day", "leap"]
+    assert frame.loc["tet", "month"] == 1
+    assert bool(frame.loc["leap", "leap"])
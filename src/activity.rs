@@ -0,0 +1,14 @@
+// Activities people usually pick a day for
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Activity {
+    Wedding,
+    MovingHouse,
+    OpeningBusiness,
+    Construction,
+    Burial,
+    Travel,
+    SigningContract,
+    Worship,
+    MedicalTreatment,
+    Planting,
+}
@@ -0,0 +1,333 @@
+// Command-line front end: conversions, pillars, solar terms, festivals, leap months and month grids
+use anyhow::{anyhow, bail, Result};
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, TimeZone};
+use clap::{Parser, Subcommand};
+use rlunar::*;
+use serde_json::{json, Value};
+
+#[derive(Parser)]
+#[command(name = "rlunar", version, about = "Vietnamese and East Asian lunisolar calendar lookups")]
+struct Cli {
+    /// Hours from UTC such as +7, -5, +5:30 or +0545 [default: +7]; festivals and calendar use the meridian of --system
+    #[arg(long, global = true, allow_hyphen_values = true)]
+    tz: Option<String>,
+    /// vi, zh-Hans, zh-Hant, ko, ja or en
+    #[arg(long, global = true, default_value = "vi")]
+    locale: String,
+    #[arg(long, global = true)]
+    json: bool,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Gregorian date to lunisolar, or lunisolar to Gregorian with --lunar
+    Convert {
+        /// YYYY-MM-DD, a leap lunisolar month written as YYYY-MML-DD
+        date: String,
+        /// HH:MM
+        #[arg(long, default_value = "00:00")]
+        time: String,
+        /// Read the date as a lunisolar date
+        #[arg(long)]
+        lunar: bool,
+    },
+    /// Can Chi of the year, month, day and hour of a Gregorian date
+    Pillars {
+        date: String,
+        #[arg(long, default_value = "00:00")]
+        time: String,
+    },
+    /// Start dates of the 24 solar terms in a Gregorian year
+    Terms { year: i32 },
+    /// Festivals of a tradition in a Gregorian year
+    Festivals {
+        year: i32,
+        /// vietnamese, chinese, korean or japanese
+        #[arg(long, default_value = "vietnamese")]
+        system: String,
+    },
+    /// Leap month of each lunisolar year in a range
+    Leap { from: i32, to: Option<i32> },
+    /// Month grid with the lunisolar day under each Gregorian day, the whole year without a month
+    Calendar {
+        year: i32,
+        month: Option<u32>,
+        /// vietnamese, chinese, korean or japanese
+        #[arg(long, default_value = "vietnamese")]
+        system: String,
+    },
+}
+
+fn parse_time_zone(value: &str) -> Result<FixedOffset> {
+    let value = value.trim();
+    let (sign, rest) = match value.strip_prefix('-') {
+        Some(rest) => (-1, rest),
+        None => (1, value.strip_prefix('+').unwrap_or(value)),
+    };
+    let (hours, minutes): (i32, i32) = match rest.split_once(':') {
+        Some((hours, minutes)) => (hours.parse()?, minutes.parse()?),
+        None if rest.len() == 4 && rest.is_ascii() => (rest[..2].parse()?, rest[2..].parse()?),
+        None => {
+            let hours: f64 = rest.parse()?;
+            if !hours.is_finite() {
+                bail!("invalid time zone {}", value);
+            }
+            (hours.trunc() as i32, (hours.fract() * 60.0).round() as i32)
+        }
+    };
+    if !(0..=14).contains(&hours) || !(0..=59).contains(&minutes) {
+        bail!("invalid time zone {}", value);
+    }
+    FixedOffset::east_opt(sign * (hours * 3600 + minutes * 60)).ok_or_else(|| anyhow!("invalid time zone {}", value))
+}
+
+fn cli_offset(cli: &Cli) -> Result<FixedOffset> {
+    parse_time_zone(cli.tz.as_deref().unwrap_or("+7"))
+}
+
+// Festivals and grids are reckoned at the tradition's own meridian, so a --tz there would be silently ignored
+fn reject_time_zone(cli: &Cli, command: &str) -> Result<()> {
+    if let Some(tz) = &cli.tz {
+        bail!("{} uses the meridian of --system, --tz {} does not apply", command, tz);
+    }
+    Ok(())
+}
+
+fn time_zone_hours(offset: &FixedOffset) -> f64 {
+    offset.local_minus_utc() as f64 / 3600.0
+}
+
+fn parse_system(value: &str) -> Result<CalendarSystem> {
+    match value.trim().to_lowercase().as_str() {
+        "vietnamese" | "vi" => Ok(CalendarSystem::Vietnamese),
+        "chinese" | "zh" => Ok(CalendarSystem::Chinese),
+        "korean" | "ko" => Ok(CalendarSystem::Korean),
+        "japanese" | "ja" => Ok(CalendarSystem::Japanese),
+        _ => bail!("unknown calendar system {}", value),
+    }
+}
+
+// The lunisolar form takes an L after the month for a leap month, as in serde_support::compact
+fn parse_date(value: &str) -> Result<(i32, u32, bool, u32)> {
+    let parts: Vec<&str> = value.trim().split('-').collect();
+    if parts.len() != 3 {
+        bail!("expected YYYY-MM-DD, got {}", value);
+    }
+    let (month, leap) = match parts[1].strip_suffix(['L', 'l']) {
+        Some(month) => (month, true),
+        None => (parts[1], false),
+    };
+    Ok((parts[0].parse()?, month.parse()?, leap, parts[2].parse()?))
+}
+
+fn parse_gregorian(value: &str) -> Result<NaiveDate> {
+    let (year, month, leap, day) = parse_date(value)?;
+    if leap {
+        bail!("a gregorian date has no leap month: {}", value);
+    }
+    NaiveDate::from_ymd_opt(year, month, day).ok_or_else(|| anyhow!("invalid gregorian date {}", value))
+}
+
+fn object_at(date: NaiveDate, time: &str, offset: &FixedOffset) -> Result<LunarDateTimeObject> {
+    let time = NaiveTime::parse_from_str(time, "%H:%M")?;
+    let gregorian_datetime: DateTime<FixedOffset> = offset.from_local_datetime(&date.and_time(time)).single()
+        .ok_or_else(|| anyhow!("invalid local time {} {}", date, time))?;
+    Ok(LunarDateTimeObject {
+        lunisolar_datetime: NaiveLunisolarDateTime::new_from_datetime(gregorian_datetime)?,
+        lunar_datetime: NaiveLunarDateTime::new_from_datetime(gregorian_datetime)?,
+        gregorian_datetime,
+    })
+}
+
+fn pillar_name(pillar: &LunarFormat, locale: &Locale) -> String {
+    match locale {
+        Locale::Vi | Locale::En => format!("{} {}", pillar.stem.localized_name(locale), pillar.branch.localized_name(locale)),
+        _ => format!("{}{}", pillar.stem.localized_name(locale), pillar.branch.localized_name(locale)),
+    }
+}
+
+fn lunisolar_label(lunisolar: &NaiveLunisolarDateTime) -> String {
+    format!("{}/{}{}/{}", lunisolar.day_lunisolar, lunisolar.month_lunisolar,
+            if lunisolar.leap_lunisolar { " nhuận" } else { "" }, lunisolar.year_lunisolar)
+}
+
+fn convert(cli: &Cli, date: &str, time: &str, lunar: bool) -> Result<Value> {
+    let offset = cli_offset(cli)?;
+    let locale = Locale::from_tag(&cli.locale).ok_or_else(|| anyhow!("unknown locale {}", cli.locale))?;
+    let gregorian = if lunar {
+        let (year, month, leap, day) = parse_date(date)?;
+        NaiveLunisolarDateTime::lunisolar_to_gregorian(day, month, year, leap, time_zone_hours(&offset))?
+    } else {
+        parse_gregorian(date)?
+    };
+    let object = object_at(gregorian, time, &offset)?;
+    if !cli.json {
+        println!("{}  →  {} âm lịch, {} năm {}",
+                 object.gregorian_datetime.format("%Y-%m-%d %H:%M %:z"),
+                 lunisolar_label(&object.lunisolar_datetime),
+                 object.lunisolar_datetime.localized_month_name(&locale),
+                 pillar_name(&object.lunar_datetime.year_lunar, &locale));
+    }
+    Ok(serde_json::to_value(object)?)
+}
+
+fn pillars(cli: &Cli, date: &str, time: &str) -> Result<Value> {
+    let offset = cli_offset(cli)?;
+    let locale = Locale::from_tag(&cli.locale).ok_or_else(|| anyhow!("unknown locale {}", cli.locale))?;
+    let lunar = object_at(parse_gregorian(date)?, time, &offset)?.lunar_datetime;
+    let rows = [("year", lunar.year_lunar), ("month", lunar.month_lunar), ("day", lunar.date_lunar), ("hour", lunar.hour_lunar)];
+    if !cli.json {
+        for (label, pillar) in rows.iter() {
+            println!("{:<6} {}", label, pillar_name(pillar, &locale));
+        }
+    }
+    Ok(Value::Object(rows.iter()
+        .map(|(label, pillar)| (label.to_string(), json!(pillar_name(pillar, &locale))))
+        .collect()))
+}
+
+fn terms(cli: &Cli, year: i32) -> Result<Value> {
+    let offset = cli_offset(cli)?;
+    let locale = Locale::from_tag(&cli.locale).ok_or_else(|| anyhow!("unknown locale {}", cli.locale))?;
+    let dates = SolarTerm::dates_in_year(year, time_zone_hours(&offset))?;
+    if !cli.json {
+        for (term, date) in dates.iter() {
+            println!("{}  {:>3}°  {}", date, term.longitude(), term.localized_name(&locale));
+        }
+    }
+    Ok(Value::Array(dates.iter()
+        .map(|(term, date)| json!({"term": term.localized_name(&locale), "longitude": term.longitude(), "date": date.to_string()}))
+        .collect()))
+}
+
+fn festivals(cli: &Cli, year: i32, system: &str) -> Result<Value> {
+    reject_time_zone(cli, "festivals")?;
+    let occurrences = festivals_in_year(&parse_system(system)?, year)?;
+    if !cli.json {
+        for occurrence in occurrences.iter() {
+            println!("{}  {}", occurrence.date, occurrence.holiday.name);
+        }
+    }
+    Ok(Value::Array(occurrences.iter()
+        .map(|occurrence| json!({"name": occurrence.holiday.name, "date": occurrence.date.to_string()}))
+        .collect()))
+}
+
+fn leap(cli: &Cli, from: i32, to: Option<i32>) -> Result<Value> {
+    let offset = cli_offset(cli)?;
+    let to = to.unwrap_or(from);
+    if from > to {
+        bail!("empty year range {}..{}", from, to);
+    }
+    let mut result = Vec::new();
+    for year in from..=to {
+        // Validates the year range of the calendar tables
+        NaiveLunisolarDateTime::days_in_lunisolar_month(1, year, false, time_zone_hours(&offset))?;
+        let month = NaiveLunisolarDateTime::leap_month_of_year(year, time_zone_hours(&offset));
+        if !cli.json {
+            match month {
+                Some(month) => println!("{}  tháng {} nhuận", year, month),
+                None => println!("{}  -", year),
+            }
+        }
+        result.push(json!({"year": year, "leap_month": month}));
+    }
+    Ok(Value::Array(result))
+}
+
+fn calendar(cli: &Cli, year: i32, month: Option<u32>, system: &str) -> Result<Value> {
+    reject_time_zone(cli, "calendar")?;
+    let system = parse_system(system)?;
+    let locale = Locale::from_tag(&cli.locale).ok_or_else(|| anyhow!("unknown locale {}", cli.locale))?;
+    if !cli.json {
+        match month {
+            Some(month) => print!("{}", render_month(&system, year, month, &locale)?),
+            None => print!("{}", render_year(&system, year, &locale)?),
+        }
+        return Ok(Value::Null);
+    }
+    let mut days = Vec::new();
+    for month in month.map_or(1..=12, |month| month..=month) {
+        days.extend(month_days(&system, year, month)?);
+    }
+    Ok(Value::Array(days.iter()
+        .map(|day| json!({
+            "gregorian": day.date.to_string(),
+            "lunisolar": {"day": day.lunisolar_day, "month": day.lunisolar_month, "leap": day.leap, "year": day.lunisolar_year},
+            "solar_term": day.solar_term.map(|term| term.localized_name(&locale)),
+            "holidays": day.holidays,
+        }))
+        .collect()))
+}
+
+fn run(cli: &Cli) -> Result<Value> {
+    match &cli.command {
+        Command::Convert { date, time, lunar } => convert(cli, date, time, *lunar),
+        Command::Pillars { date, time } => pillars(cli, date, time),
+        Command::Terms { year } => terms(cli, *year),
+        Command::Festivals { year, system } => festivals(cli, *year, system),
+        Command::Leap { from, to } => leap(cli, *from, *to),
+        Command::Calendar { year, month, system } => calendar(cli, *year, *month, system),
+    }
+}
+
+fn main() {
+    let cli = Cli::parse();
+    match run(&cli) {
+        Ok(value) => {
+            if cli.json {
+                println!("{}", serde_json::to_string_pretty(&value).unwrap());
+            }
+        }
+        Err(e) => {
+            eprintln!("rlunar: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_time_zone() {
+        assert_eq!(parse_time_zone("+7").unwrap().local_minus_utc(), 7 * 3600);
+        assert_eq!(parse_time_zone("-5").unwrap().local_minus_utc(), -5 * 3600);
+        assert_eq!(parse_time_zone("+5:30").unwrap().local_minus_utc(), 5 * 3600 + 1800);
+        assert_eq!(parse_time_zone("+0545").unwrap().local_minus_utc(), 5 * 3600 + 2700);
+        assert!(parse_time_zone("+25").is_err());
+        assert!(parse_time_zone("+aé1").is_err());
+        assert!(parse_time_zone("+NaN").is_err());
+        assert!(parse_time_zone("+-5").is_err());
+        assert!(parse_time_zone("+5:-30").is_err());
+    }
+
+    #[test]
+    fn test_time_zone_scope() {
+        let cli = Cli::parse_from(["rlunar", "--tz", "+9", "festivals", "2024"]);
+        assert!(run(&cli).is_err());
+        let cli = Cli::parse_from(["rlunar", "calendar", "2024", "2", "--tz", "+9"]);
+        assert!(run(&cli).is_err());
+        let cli = Cli::parse_from(["rlunar", "--json", "festivals", "2024"]);
+        assert!(run(&cli).is_ok());
+    }
+
+    #[test]
+    fn test_parse_date() {
+        assert_eq!(parse_date("2023-02L-10").unwrap(), (2023, 2, true, 10));
+        assert!(parse_gregorian("2023-02L-10").is_err());
+        assert!(parse_date("2023-02").is_err());
+    }
+
+    #[test]
+    fn test_convert() {
+        let offset = parse_time_zone("+7").unwrap();
+        let object = object_at(parse_gregorian("2024-02-10").unwrap(), "00:00", &offset).unwrap();
+        assert_eq!(lunisolar_label(&object.lunisolar_datetime), "1/1/2024");
+        assert_eq!(pillar_name(&object.lunar_datetime.year_lunar, &Locale::Vi), "Giáp Thìn");
+        assert_eq!(pillar_name(&object.lunar_datetime.year_lunar, &Locale::ZhHans), "甲辰");
+    }
+}
@@ -0,0 +1,305 @@
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, FixedOffset, NaiveDate, TimeZone};
+use super::lunisolar_datetime::*;
+use super::solar_term::*;
+use super::calendar_system::*;
+use super::holiday::*;
+use super::localization::*;
+
+// Width of a day column, enough for "1/12L"
+const CELL_WIDTH: usize = 6;
+const WEEK_WIDTH: usize = CELL_WIDTH * 7;
+const YEAR_VIEW_COLUMNS: usize = 3;
+
+// Monday first
+fn weekday_names(locale: &Locale) -> [&'static str; 7] {
+    match locale {
+        Locale::Vi => { ["T2", "T3", "T4", "T5", "T6", "T7", "CN"] }
+        Locale::ZhHans | Locale::ZhHant => { ["一", "二", "三", "四", "五", "六", "日"] }
+        Locale::Ko => { ["월", "화", "수", "목", "금", "토", "일"] }
+        Locale::Ja => { ["月", "火", "水", "木", "金", "土", "日"] }
+        Locale::En => { ["Mo", "Tu", "We", "Th", "Fr", "Sa", "Su"] }
+    }
+}
+
+fn month_title(year: i32, month: u32, locale: &Locale) -> String {
+    match locale {
+        Locale::Vi => format!("Tháng {}/{}", month, year),
+        Locale::ZhHans | Locale::ZhHant | Locale::Ja => format!("{}年{}月", year, month),
+        Locale::Ko => format!("{}년 {}월", year, month),
+        Locale::En => NaiveDate::from_ymd_opt(year, month, 1).map(|date| date.format("%B %Y").to_string()).unwrap_or_default(),
+    }
+}
+
+fn lunar_month_note(month: u32, leap: bool, locale: &Locale) -> String {
+    let name = lunisolar_month_name(month, leap, locale).unwrap_or_default();
+    match locale {
+        Locale::Vi => format!("{} âm lịch", name),
+        Locale::ZhHans => format!("农历{}", name),
+        Locale::ZhHant => format!("農曆{}", name),
+        Locale::Ko => format!("음력 {}", name),
+        Locale::Ja => format!("旧暦{}", name),
+        Locale::En => format!("{} (lunar)", name),
+    }
+}
+
+// Terminal columns, two for Hangul and the CJK ideographs of the headers and titles
+fn display_width(text: &str) -> usize {
+    text.chars()
+        .map(|c| match c as u32 {
+            0x1100..=0x115F | 0x2E80..=0xA4CF | 0xAC00..=0xD7A3 | 0xF900..=0xFAFF | 0xFF00..=0xFF60 => 2,
+            _ => 1,
+        })
+        .sum()
+}
+
+fn pad_right(text: &str, width: usize) -> String {
+    format!("{}{}", text, " ".repeat(width.saturating_sub(display_width(text))))
+}
+
+fn pad_left(text: &str, width: usize) -> String {
+    format!("{}{}", " ".repeat(width.saturating_sub(display_width(text))), text)
+}
+
+fn center(text: &str, width: usize) -> String {
+    let padding = width.saturating_sub(display_width(text));
+    format!("{}{}{}", " ".repeat(padding / 2), text, " ".repeat(padding - padding / 2))
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct CalendarDay {
+    pub date: NaiveDate,
+    pub lunisolar_day: u32,
+    pub lunisolar_month: u32,
+    pub leap: bool,
+    pub lunisolar_year: i32,
+    // Term beginning on this day
+    pub solar_term: Option<SolarTerm>,
+    pub holidays: Vec<&'static str>,
+}
+
+impl CalendarDay {
+    // Lunar day under the gregorian one, month number added on the first day as in "1/4", L marking a leap month
+    fn lunisolar_label(&self) -> String {
+        match self.lunisolar_day {
+            1 => format!("1/{}{}", self.lunisolar_month, if self.leap { "L" } else { "" }),
+            day => day.to_string(),
+        }
+    }
+
+    // * for a holiday, + for the start of a solar term
+    fn marker(&self) -> char {
+        if !self.holidays.is_empty() {
+            '*'
+        } else if self.solar_term.is_some() {
+            '+'
+        } else {
+            ' '
+        }
+    }
+}
+
+// Day after a lunisolar date, walking the month lengths instead of converting every day
+fn next_lunisolar_day(day: &CalendarDay, time_zone: f64) -> Result<(u32, u32, bool, i32)> {
+    let (month, leap, year) = (day.lunisolar_month, day.leap, day.lunisolar_year);
+    if day.lunisolar_day < NaiveLunisolarDateTime::days_in_lunisolar_month(month, year, leap, time_zone)? {
+        return Ok((day.lunisolar_day + 1, month, leap, year));
+    }
+    if !leap && NaiveLunisolarDateTime::leap_month_of_year(year, time_zone) == Some(month) {
+        return Ok((1, month, true, year));
+    }
+    match month {
+        12 => Ok((1, 1, false, year + 1)),
+        _ => Ok((1, month + 1, false, year)),
+    }
+}
+
+// Every day of a gregorian month with its lunisolar date, terms and festivals, reckoned at the tradition's meridian.
+// The month lengths are only known from MIN_YEAR on, so the last days of lunisolar 1899 in January 1900 are converted one by one.
+pub fn month_days(system: &CalendarSystem, year: i32, month: u32) -> Result<Vec<CalendarDay>> {
+    let time_zone = system.time_zone();
+    let first = NaiveDate::from_ymd_opt(year, month, 1).ok_or_else(|| anyhow!("invalid month {}/{}", month, year))?;
+    let offset = FixedOffset::east_opt((time_zone * 3600.0) as i32).ok_or_else(|| anyhow!("invalid time zone {}", time_zone))?;
+    let convert = |date: NaiveDate| -> Result<(u32, u32, bool, i32)> {
+        let datetime = offset.from_local_datetime(&date.and_hms_opt(0, 0, 0).unwrap()).unwrap();
+        let lunisolar = NaiveLunisolarDateTime::new_from_datetime(datetime)?;
+        Ok((lunisolar.day_lunisolar, lunisolar.month_lunisolar, lunisolar.leap_lunisolar, lunisolar.year_lunisolar as i32))
+    };
+    let terms = SolarTerm::dates_in_year(year, time_zone)?;
+    let festivals = festivals_in_year(system, year)?;
+
+    let mut result: Vec<CalendarDay> = Vec::new();
+    for date in first.iter_days().take_while(|date| date.month() == month) {
+        let (lunisolar_day, lunisolar_month, leap, lunisolar_year) = match result.last() {
+            Some(previous) if previous.lunisolar_year >= MIN_YEAR => next_lunisolar_day(previous, time_zone)?,
+            _ => convert(date)?,
+        };
+        result.push(CalendarDay {
+            date,
+            lunisolar_day,
+            lunisolar_month,
+            leap,
+            lunisolar_year,
+            solar_term: terms.iter().find(|(_, term_date)| *term_date == date).map(|(term, _)| *term),
+            holidays: festivals.iter()
+                .filter(|occurrence| occurrence.date == date)
+                .map(|occurrence| occurrence.holiday.name)
+                .collect(),
+        });
+    }
+    Ok(result)
+}
+
+// Title, weekday header and two lines per week, each padded to WEEK_WIDTH
+fn grid_lines(days: &[CalendarDay], locale: &Locale) -> Vec<String> {
+    let first = days[0].date;
+    let mut lines = vec![
+        center(&month_title(first.year(), first.month(), locale), WEEK_WIDTH),
+        weekday_names(locale).iter().map(|weekday| format!("{}  ", pad_left(weekday, 4))).collect(),
+    ];
+    let blank = " ".repeat(CELL_WIDTH * first.weekday().num_days_from_monday() as usize);
+    let (mut gregorian_line, mut lunisolar_line) = (blank.clone(), blank);
+    for day in days {
+        gregorian_line.push_str(&format!("{:>4}{} ", day.date.day(), day.marker()));
+        // Under the day number, labels such as "1/12L" taking the marker column
+        lunisolar_line.push_str(&format!("{:<6}", format!("{:>4}", day.lunisolar_label())));
+        if day.date.weekday().num_days_from_monday() == 6 {
+            lines.push(std::mem::take(&mut gregorian_line));
+            lines.push(std::mem::take(&mut lunisolar_line));
+        }
+    }
+    if !gregorian_line.is_empty() {
+        lines.push(gregorian_line);
+        lines.push(lunisolar_line);
+    }
+    lines.iter().map(|line| pad_right(line, WEEK_WIDTH)).collect()
+}
+
+// One line per lunar month start, solar term and holiday
+fn note_lines(days: &[CalendarDay], locale: &Locale) -> Vec<String> {
+    let mut lines = Vec::new();
+    for day in days {
+        let date = match locale {
+            Locale::Vi => day.date.format("%d/%m"),
+            _ => day.date.format("%m-%d"),
+        };
+        if day.lunisolar_day == 1 {
+            lines.push(format!("  {}  {}", date, lunar_month_note(day.lunisolar_month, day.leap, locale)));
+        }
+        if let Some(term) = day.solar_term {
+            lines.push(format!("+ {}  {}", date, term.localized_name(locale)));
+        }
+        for name in day.holidays.iter() {
+            lines.push(format!("* {}  {}", date, name));
+        }
+    }
+    lines
+}
+
+fn join_lines(lines: &[String]) -> String {
+    lines.iter().map(|line| line.trim_end()).collect::<Vec<_>>().join("\n") + "\n"
+}
+
+// cal-like grid of a gregorian month with the lunar day under each day, followed by the notes.
+// Titles, weekdays, month and term names follow the locale, festival names are those of the calendar system.
+pub fn render_month(system: &CalendarSystem, year: i32, month: u32, locale: &Locale) -> Result<String> {
+    let days = month_days(system, year, month)?;
+    let mut lines = grid_lines(&days, locale);
+    lines.push(String::new());
+    lines.extend(note_lines(&days, locale));
+    Ok(join_lines(&lines))
+}
+
+// Twelve grids, three side by side, with the notes of the whole year at the end
+pub fn render_year(system: &CalendarSystem, year: i32, locale: &Locale) -> Result<String> {
+    let months = (1..=12).map(|month| month_days(system, year, month)).collect::<Result<Vec<_>>>()?;
+    let mut lines = vec![format!("{:^width$}", year, width = WEEK_WIDTH * YEAR_VIEW_COLUMNS + 2 * (YEAR_VIEW_COLUMNS - 1)), String::new()];
+    for row in months.chunks(YEAR_VIEW_COLUMNS) {
+        let grids: Vec<Vec<String>> = row.iter().map(|days| grid_lines(days, locale)).collect();
+        let height = grids.iter().map(|grid| grid.len()).max().unwrap_or(0);
+        for index in 0..height {
+            lines.push(grids.iter()
+                .map(|grid| grid.get(index).cloned().unwrap_or_else(|| " ".repeat(WEEK_WIDTH)))
+                .collect::<Vec<_>>()
+                .join("  "));
+        }
+        lines.push(String::new());
+    }
+    lines.extend(months.iter().flat_map(|days| note_lines(days, locale)));
+    Ok(join_lines(&lines))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_month_days() {
+        // The leap second month of Quý Mão begins on 22 March 2023
+        let days = month_days(&CalendarSystem::Vietnamese, 2023, 3).unwrap();
+        assert_eq!(days.len(), 31);
+        assert_eq!((days[0].lunisolar_day, days[0].lunisolar_month, days[0].leap), (10, 2, false));
+        assert_eq!((days[21].lunisolar_day, days[21].lunisolar_month, days[21].leap), (1, 2, true));
+        assert_eq!(days[5].solar_term, Some(SolarTerm::KinhTrap));
+        for day in days.iter() {
+            let offset = FixedOffset::east_opt(7 * 3600).unwrap();
+            let datetime = offset.from_local_datetime(&day.date.and_hms_opt(0, 0, 0).unwrap()).unwrap();
+            let expected = NaiveLunisolarDateTime::new_from_datetime(datetime).unwrap();
+            assert_eq!((day.lunisolar_day, day.lunisolar_month, day.leap), (expected.day_lunisolar, expected.month_lunisolar, expected.leap_lunisolar));
+        }
+    }
+
+    #[test]
+    fn test_render_month() {
+        let text = render_month(&CalendarSystem::Vietnamese, 2024, 2, &Locale::Vi).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0].trim(), "Tháng 2/2024");
+        assert_eq!(lines[1], "  T2    T3    T4    T5    T6    T7    CN");
+        // 1 February 2024 is a Thursday, the 22nd of the twelfth lunar month
+        assert_eq!(lines[2], "                     1     2*    3     4+");
+        assert_eq!(lines[3], "                    22    23    24    25");
+        // Tết Nguyên Đán on Saturday the 10th
+        assert!(lines[4].ends_with("9*   10*   11"));
+        assert!(lines[5].ends_with("30   1/1     2"));
+        assert!(text.contains("+ 04/02  Lập xuân"));
+        assert!(text.contains("  10/02  Tháng Giêng âm lịch"));
+    }
+
+    #[test]
+    fn test_render_year() {
+        let text = render_year(&CalendarSystem::Vietnamese, 2023, &Locale::Vi).unwrap();
+        assert!(text.lines().any(|line| line.contains("Tháng 1/2023") && line.contains("Tháng 3/2023")));
+        assert!(text.contains("1/2L"));
+        assert!(text.contains("  22/03  Tháng Hai nhuận âm lịch"));
+        assert!(text.lines().all(|line| line.chars().count() <= WEEK_WIDTH * 3 + 4));
+
+        let text = render_year(&CalendarSystem::Chinese, 2023, &Locale::ZhHans).unwrap();
+        assert!(text.lines().any(|line| line.contains("2023年1月") && line.contains("2023年3月")));
+        assert!(text.lines().all(|line| display_width(line) <= WEEK_WIDTH * 3 + 4));
+    }
+
+    #[test]
+    fn test_render_month_locale() {
+        let text = render_month(&CalendarSystem::Chinese, 2024, 2, &Locale::ZhHans).unwrap();
+        let lines: Vec<&str> = text.lines().collect();
+        assert_eq!(lines[0].trim(), "2024年2月");
+        assert_eq!(lines[1], "  一    二    三    四    五    六    日");
+        assert_eq!(display_width(lines[1]), WEEK_WIDTH - 2);
+        assert!(text.contains("+ 02-04  立春"));
+        assert!(text.contains("  02-10  农历正月"));
+
+        let text = render_month(&CalendarSystem::Vietnamese, 2024, 2, &Locale::En).unwrap();
+        assert!(text.starts_with(&center("February 2024", WEEK_WIDTH).trim_end().to_string()));
+        assert!(text.contains("  Mo    Tu"));
+        assert!(text.contains("  02-10  First Month (lunar)"));
+    }
+
+    #[test]
+    fn test_january_1900() {
+        // 1 January 1900 is the first of the twelfth month of lunisolar 1899, Tết falls on 31 January
+        let days = month_days(&CalendarSystem::Vietnamese, 1900, 1).unwrap();
+        assert_eq!((days[0].lunisolar_day, days[0].lunisolar_month, days[0].lunisolar_year), (1, 12, 1899));
+        assert_eq!((days[30].lunisolar_day, days[30].lunisolar_month, days[30].lunisolar_year), (1, 1, 1900));
+        assert!(render_month(&CalendarSystem::Vietnamese, 1900, 1, &Locale::Vi).is_ok());
+    }
+}
@@ -0,0 +1,31 @@
+use super::zodiac_and_element::*;
+
+// Lunisolar calendar traditions, which differ by the meridian the new moons and terms are reckoned at
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum CalendarSystem {
+    Vietnamese,
+    Chinese,
+    Korean,
+    Japanese,
+}
+
+impl CalendarSystem {
+    // Hours from UTC of the reference meridian: 105°E for Vietnam, 120°E for China, 135°E for Korea and Japan
+    pub fn time_zone(&self) -> f64 {
+        match self {
+            CalendarSystem::Vietnamese => { 7.0 }
+            CalendarSystem::Chinese => { 8.0 }
+            CalendarSystem::Korean => { 9.0 }
+            CalendarSystem::Japanese => { 9.0 }
+        }
+    }
+
+    pub fn zodiac_tradition(&self) -> ZodiacTradition {
+        match self {
+            CalendarSystem::Vietnamese => { ZodiacTradition::Vietnamese }
+            CalendarSystem::Chinese => { ZodiacTradition::Chinese }
+            CalendarSystem::Korean => { ZodiacTradition::Korean }
+            CalendarSystem::Japanese => { ZodiacTradition::Japanese }
+        }
+    }
+}
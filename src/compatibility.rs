@@ -0,0 +1,220 @@
+use super::lunar_datetime::*;
+use super::zodiac_and_element::*;
+use super::LunarDateTimeObject;
+
+// Nạp âm element of each pair in the sexagenary cycle, starting with Giáp Tý - Ất Sửu (Hải Trung Kim)
+const NAP_AM: [(Element, &str); 30] = [
+    (Element::Metal, "Hải Trung Kim"),
+    (Element::Fire, "Lư Trung Hỏa"),
+    (Element::Wood, "Đại Lâm Mộc"),
+    (Element::Earth, "Lộ Bàng Thổ"),
+    (Element::Metal, "Kiếm Phong Kim"),
+    (Element::Fire, "Sơn Đầu Hỏa"),
+    (Element::Water, "Giản Hạ Thủy"),
+    (Element::Earth, "Thành Đầu Thổ"),
+    (Element::Metal, "Bạch Lạp Kim"),
+    (Element::Wood, "Dương Liễu Mộc"),
+    (Element::Water, "Tuyền Trung Thủy"),
+    (Element::Earth, "Ốc Thượng Thổ"),
+    (Element::Fire, "Tích Lịch Hỏa"),
+    (Element::Wood, "Tùng Bách Mộc"),
+    (Element::Water, "Trường Lưu Thủy"),
+    (Element::Metal, "Sa Trung Kim"),
+    (Element::Fire, "Sơn Hạ Hỏa"),
+    (Element::Wood, "Bình Địa Mộc"),
+    (Element::Earth, "Bích Thượng Thổ"),
+    (Element::Metal, "Kim Bạch Kim"),
+    (Element::Fire, "Phú Đăng Hỏa"),
+    (Element::Water, "Thiên Hà Thủy"),
+    (Element::Earth, "Đại Trạch Thổ"),
+    (Element::Metal, "Thoa Xuyến Kim"),
+    (Element::Wood, "Tang Đố Mộc"),
+    (Element::Water, "Đại Khê Thủy"),
+    (Element::Earth, "Sa Trung Thổ"),
+    (Element::Fire, "Thiên Thượng Hỏa"),
+    (Element::Wood, "Thạch Lựu Mộc"),
+    (Element::Water, "Đại Hải Thủy"),
+];
+
+impl LunarFormat {
+    // Position in the 60-pair cycle, Giáp Tý being 0 and Quý Hợi 59
+    pub fn sexagenary_index(&self) -> u32 {
+        (6 * self.stem.to_numeric() + 60 - 5 * self.branch.to_numeric()) % 60
+    }
+
+    pub fn nap_am_element(&self) -> Element {
+        NAP_AM[(self.sexagenary_index() / 2) as usize].0
+    }
+
+    pub fn nap_am_name(&self) -> &'static str {
+        NAP_AM[(self.sexagenary_index() / 2) as usize].1
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum BranchRelation {
+    // Lục hợp
+    SixHarmony,
+    // Tam hợp
+    ThreeHarmony,
+    // Lục xung
+    Clash,
+    // Lục hại
+    Harm,
+    Neutral,
+}
+
+impl BranchRelation {
+    pub fn between(first: &EarthlyBranch, second: &EarthlyBranch) -> BranchRelation {
+        let a = first.to_numeric();
+        let b = second.to_numeric();
+        let diff = (a + 12 - b) % 12;
+        match ((a + b) % 12, diff) {
+            (1, _) => BranchRelation::SixHarmony,
+            (7, _) => BranchRelation::Harm,
+            (_, 4) | (_, 8) => BranchRelation::ThreeHarmony,
+            (_, 6) => BranchRelation::Clash,
+            _ => BranchRelation::Neutral,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum StemRelation {
+    // Thiên can hợp, e.g. Giáp - Kỷ
+    Combination,
+    // Thiên can xung, e.g. Giáp - Canh
+    Clash,
+    Neutral,
+}
+
+impl StemRelation {
+    pub fn between(first: &HeavenlyStem, second: &HeavenlyStem) -> StemRelation {
+        match (first.to_numeric() + 10 - second.to_numeric()) % 10 {
+            5 => StemRelation::Combination,
+            4 | 6 => StemRelation::Clash,
+            _ => StemRelation::Neutral,
+        }
+    }
+}
+
+// Relation of the first element towards the second
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ElementRelation {
+    Same,
+    Generates,
+    GeneratedBy,
+    Overcomes,
+    OvercomeBy,
+}
+
+impl ElementRelation {
+    pub fn between(first: &Element, second: &Element) -> ElementRelation {
+        if first == second {
+            ElementRelation::Same
+        } else if first.generates() == *second {
+            ElementRelation::Generates
+        } else if second.generates() == *first {
+            ElementRelation::GeneratedBy
+        } else if first.overcomes() == *second {
+            ElementRelation::Overcomes
+        } else {
+            ElementRelation::OvercomeBy
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct CompatibilityReport {
+    pub branch_relation: BranchRelation,
+    pub stem_relation: StemRelation,
+    pub element_relation: ElementRelation,
+}
+
+impl CompatibilityReport {
+    pub fn from_year_pillars(first: &LunarFormat, second: &LunarFormat) -> CompatibilityReport {
+        CompatibilityReport {
+            branch_relation: BranchRelation::between(&first.branch, &second.branch),
+            stem_relation: StemRelation::between(&first.stem, &second.stem),
+            element_relation: ElementRelation::between(&first.nap_am_element(), &second.nap_am_element()),
+        }
+    }
+
+    // Positive means a good match, negative a poor one
+    pub fn score(&self) -> i32 {
+        let branch = match self.branch_relation {
+            BranchRelation::SixHarmony | BranchRelation::ThreeHarmony => 2,
+            BranchRelation::Clash => -2,
+            BranchRelation::Harm => -1,
+            BranchRelation::Neutral => 0,
+        };
+        let stem = match self.stem_relation {
+            StemRelation::Combination => 1,
+            StemRelation::Clash => -1,
+            StemRelation::Neutral => 0,
+        };
+        let element = match self.element_relation {
+            ElementRelation::Generates | ElementRelation::GeneratedBy => 1,
+            ElementRelation::Same => 0,
+            ElementRelation::Overcomes | ElementRelation::OvercomeBy => -1,
+        };
+        branch + stem + element
+    }
+}
+
+impl LunarDateTimeObject {
+    pub fn compatibility_with(&self, other: &LunarDateTimeObject) -> CompatibilityReport {
+        CompatibilityReport::from_year_pillars(&self.lunar_datetime.year_lunar, &other.lunar_datetime.year_lunar)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use super::*;
+
+    #[rstest]
+    #[case(HeavenlyStem::HS_GIAP, EarthlyBranch::EB_RAT, 0, Element::Metal)]
+    #[case(HeavenlyStem::HS_AT, EarthlyBranch::EB_BUFFALO, 1, Element::Metal)]
+    #[case(HeavenlyStem::HS_GIAP, EarthlyBranch::EB_DOG, 10, Element::Fire)]
+    #[case(HeavenlyStem::HS_QUY, EarthlyBranch::EB_CAT, 39, Element::Metal)]
+    #[case(HeavenlyStem::HS_QUY, EarthlyBranch::EB_PIG, 59, Element::Water)]
+    fn test_nap_am(#[case] stem: HeavenlyStem, #[case] branch: EarthlyBranch,
+                   #[case] expected_index: u32, #[case] expected_element: Element) {
+        let pillar = LunarFormat::new(stem, branch);
+        assert_eq!(pillar.sexagenary_index(), expected_index);
+        assert_eq!(pillar.nap_am_element(), expected_element);
+    }
+
+    #[rstest]
+    #[case(EarthlyBranch::EB_RAT, EarthlyBranch::EB_BUFFALO, BranchRelation::SixHarmony)]
+    #[case(EarthlyBranch::EB_TIGER, EarthlyBranch::EB_PIG, BranchRelation::SixHarmony)]
+    #[case(EarthlyBranch::EB_MONKEY, EarthlyBranch::EB_DRAGON, BranchRelation::ThreeHarmony)]
+    #[case(EarthlyBranch::EB_RAT, EarthlyBranch::EB_HORSE, BranchRelation::Clash)]
+    #[case(EarthlyBranch::EB_RAT, EarthlyBranch::EB_GOAT, BranchRelation::Harm)]
+    #[case(EarthlyBranch::EB_RAT, EarthlyBranch::EB_TIGER, BranchRelation::Neutral)]
+    fn test_branch_relation(#[case] first: EarthlyBranch, #[case] second: EarthlyBranch, #[case] expected: BranchRelation) {
+        assert_eq!(BranchRelation::between(&first, &second), expected);
+        assert_eq!(BranchRelation::between(&second, &first), expected);
+    }
+
+    #[test]
+    fn test_stem_relation() {
+        assert_eq!(StemRelation::between(&HeavenlyStem::HS_GIAP, &HeavenlyStem::HS_KY), StemRelation::Combination);
+        assert_eq!(StemRelation::between(&HeavenlyStem::HS_GIAP, &HeavenlyStem::HS_CANH), StemRelation::Clash);
+        assert_eq!(StemRelation::between(&HeavenlyStem::HS_MAU, &HeavenlyStem::HS_GIAP), StemRelation::Clash);
+        assert_eq!(StemRelation::between(&HeavenlyStem::HS_GIAP, &HeavenlyStem::HS_AT), StemRelation::Neutral);
+    }
+
+    #[test]
+    fn test_compatibility_between_birth_dates() {
+        // 1984 Giáp Tý (Hải Trung Kim) against 1990 Canh Ngọ (Lộ Bàng Thổ)
+        let first = LunarDateTimeObject::new_from_gregorian_datetime(15, 6, 1984, 8, 0, 7).unwrap();
+        let second = LunarDateTimeObject::new_from_gregorian_datetime(15, 6, 1990, 8, 0, 7).unwrap();
+        let report = first.compatibility_with(&second);
+        assert_eq!(report.branch_relation, BranchRelation::Clash);
+        assert_eq!(report.stem_relation, StemRelation::Clash);
+        assert_eq!(report.element_relation, ElementRelation::GeneratedBy);
+        assert_eq!(report.score(), -2);
+    }
+}
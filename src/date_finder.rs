@@ -0,0 +1,176 @@
+use anyhow::{Result, bail};
+use chrono::{Datelike, NaiveDate};
+use super::lunar_datetime::*;
+use super::zodiac_and_element::*;
+use super::compatibility::*;
+use super::twelve_spirit::*;
+use super::day_officer::*;
+use super::activity::*;
+use super::star::*;
+use super::taboo_day::*;
+use super::LunarDateTimeObject;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum ScoreReason {
+    DayOfficer(DayOfficer),
+    Spirit(TwelveSpirit),
+    Star(String, Fortune),
+    Taboo(TabooDay),
+    // Relation between the participant's year branch and the day branch
+    Age(BranchRelation),
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Reason {
+    pub kind: ScoreReason,
+    pub weight: i32,
+}
+
+#[derive(Clone, Debug)]
+pub struct ScoredDate {
+    pub date: LunarDateTimeObject,
+    pub score: i32,
+    pub reasons: Vec<Reason>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct DateQuery {
+    pub activity: Activity,
+    // Both ends are included
+    pub from: NaiveDate,
+    pub to: NaiveDate,
+    pub time_zone: i64,
+    // Lunisolar birth year of the participant, if age clashes should be checked
+    pub birth_year: Option<u32>,
+}
+
+impl DateQuery {
+    pub fn new(activity: Activity, from: NaiveDate, to: NaiveDate, time_zone: i64) -> DateQuery {
+        DateQuery {
+            activity,
+            from,
+            to,
+            time_zone,
+            birth_year: None,
+        }
+    }
+
+    pub fn with_birth_year(mut self, birth_year: u32) -> DateQuery {
+        self.birth_year = Some(birth_year);
+        self
+    }
+
+    fn officer_weight(&self, officer: &DayOfficer) -> i32 {
+        if officer.good_activities().contains(&self.activity) {
+            3
+        } else if officer.bad_activities().contains(&self.activity) {
+            -3
+        } else {
+            match officer.fortune() {
+                Fortune::Good => 1,
+                Fortune::Neutral => 0,
+                Fortune::Bad => -1,
+            }
+        }
+    }
+
+    fn age_weight(relation: &BranchRelation) -> i32 {
+        match relation {
+            BranchRelation::Clash => -4,
+            BranchRelation::Harm => -2,
+            BranchRelation::SixHarmony | BranchRelation::ThreeHarmony => 1,
+            BranchRelation::Neutral => 0,
+        }
+    }
+
+    pub fn score(&self, date: &LunarDateTimeObject, stars: &StarTable) -> Vec<Reason> {
+        let mut reasons = Vec::new();
+
+        let officer = date.day_officer();
+        reasons.push(Reason { weight: self.officer_weight(&officer), kind: ScoreReason::DayOfficer(officer) });
+
+        let spirit = TwelveSpirit::of_day(&date.lunar_datetime);
+        let weight = if spirit.is_hoang_dao() { 2 } else { -1 };
+        reasons.push(Reason { weight, kind: ScoreReason::Spirit(spirit) });
+
+        for star in stars.evaluate(date) {
+            let weight = if star.fortune == Fortune::Good { 1 } else { -1 };
+            reasons.push(Reason { weight, kind: ScoreReason::Star(star.name.clone(), star.fortune) });
+        }
+
+        for taboo in taboos(date) {
+            reasons.push(Reason { weight: -3, kind: ScoreReason::Taboo(taboo) });
+        }
+
+        if let Some(birth_year) = self.birth_year {
+            let year_branch = EarthlyBranch::lunar_y_from_lunisolar_y(birth_year);
+            let relation = BranchRelation::between(&year_branch, &date.lunar_datetime.date_lunar.branch);
+            if relation != BranchRelation::Neutral {
+                reasons.push(Reason { weight: DateQuery::age_weight(&relation), kind: ScoreReason::Age(relation) });
+            }
+        }
+        reasons
+    }
+
+    // Candidate days from best to worst; days with the same score keep calendar order
+    pub fn rank(&self, stars: &StarTable) -> Result<Vec<ScoredDate>> {
+        if self.from > self.to {
+            bail!("date range starts after it ends");
+        }
+        let mut result = Vec::new();
+        for day in self.from.iter_days().take_while(|day| *day <= self.to) {
+            let date = LunarDateTimeObject::new_from_gregorian_datetime(
+                day.day(), day.month(), day.year(), 12, 0, self.time_zone)?;
+            let reasons = self.score(&date, stars);
+            let score = reasons.iter().map(|reason| reason.weight).sum();
+            result.push(ScoredDate { date, score, reasons });
+        }
+        result.sort_by_key(|scored| std::cmp::Reverse(scored.score));
+        Ok(result)
+    }
+}
+
+pub fn find_auspicious_dates(query: &DateQuery) -> Result<Vec<ScoredDate>> {
+    query.rank(&StarTable::default())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rank_wedding_days() {
+        let query = DateQuery::new(Activity::Wedding,
+                                   NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(),
+                                   NaiveDate::from_ymd_opt(2024, 2, 29).unwrap(), 7);
+        let result = find_auspicious_dates(&query).unwrap();
+        assert_eq!(result.len(), 29);
+        for pair in result.windows(2) {
+            assert!(pair[0].score >= pair[1].score);
+        }
+        for scored in &result {
+            assert_eq!(scored.score, scored.reasons.iter().map(|r| r.weight).sum::<i32>());
+        }
+
+        // Lunar 13/1 is both Tam Nương and Dương Công kỵ nhật
+        let tam_nuong = result.iter().find(|s| s.date.gregorian_datetime.day() == 22).unwrap();
+        assert!(tam_nuong.reasons.contains(&Reason { kind: ScoreReason::Taboo(TabooDay::TamNuong), weight: -3 }));
+    }
+
+    #[test]
+    fn test_age_clash() {
+        let day = NaiveDate::from_ymd_opt(2024, 2, 10).unwrap();
+        // Giáp Thìn day against a Giáp Tuất (1994) participant
+        let query = DateQuery::new(Activity::MovingHouse, day, day, 7).with_birth_year(1994);
+        let result = find_auspicious_dates(&query).unwrap();
+        assert!(result[0].reasons.contains(&Reason { kind: ScoreReason::Age(BranchRelation::Clash), weight: -4 }));
+    }
+
+    #[test]
+    fn test_invalid_range() {
+        let query = DateQuery::new(Activity::Travel,
+                                   NaiveDate::from_ymd_opt(2024, 3, 1).unwrap(),
+                                   NaiveDate::from_ymd_opt(2024, 2, 1).unwrap(), 7);
+        assert!(find_auspicious_dates(&query).is_err());
+    }
+}
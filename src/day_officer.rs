@@ -0,0 +1,130 @@
+use chrono::{Datelike, DateTime, FixedOffset};
+use super::lunar_datetime::*;
+use super::zodiac_and_element::*;
+use super::solar_term::*;
+use super::activity::*;
+use super::LunarDateTimeObject;
+
+// Thập nhị trực
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum DayOfficer {
+    Kien,
+    Tru,
+    Man,
+    Binh,
+    Dinh,
+    Chap,
+    Pha,
+    Nguy,
+    Thanh,
+    Thu,
+    Khai,
+    Be,
+}
+
+impl DayOfficer {
+    pub fn from_numeric(num: u32) -> DayOfficer {
+        match num % 12 {
+            0 => DayOfficer::Kien,
+            1 => DayOfficer::Tru,
+            2 => DayOfficer::Man,
+            3 => DayOfficer::Binh,
+            4 => DayOfficer::Dinh,
+            5 => DayOfficer::Chap,
+            6 => DayOfficer::Pha,
+            7 => DayOfficer::Nguy,
+            8 => DayOfficer::Thanh,
+            9 => DayOfficer::Thu,
+            10 => DayOfficer::Khai,
+            _ => DayOfficer::Be,
+        }
+    }
+
+    // Kiến falls on the day whose branch is the branch of the solar-term month
+    pub fn from_branches(solar_month: &EarthlyBranch, day: &EarthlyBranch) -> DayOfficer {
+        DayOfficer::from_numeric(day.to_numeric() + 12 - solar_month.to_numeric())
+    }
+
+    pub fn from_datetime(datetime: DateTime<FixedOffset>) -> DayOfficer {
+        let solar_month = SolarTerm::from_datetime(datetime).solar_month_branch();
+        let day = EarthlyBranch::lunar_d_from_gregorian_ymd(datetime.day(), datetime.month(), datetime.year() as u32);
+        DayOfficer::from_branches(&solar_month, &day)
+    }
+
+    pub fn fortune(&self) -> Fortune {
+        match self {
+            DayOfficer::Tru | DayOfficer::Dinh | DayOfficer::Chap |
+            DayOfficer::Nguy | DayOfficer::Thanh | DayOfficer::Khai => { Fortune::Good }
+            DayOfficer::Kien | DayOfficer::Man | DayOfficer::Binh | DayOfficer::Thu => { Fortune::Neutral }
+            DayOfficer::Pha | DayOfficer::Be => { Fortune::Bad }
+        }
+    }
+
+    pub fn good_activities(&self) -> &'static [Activity] {
+        match self {
+            DayOfficer::Kien => &[Activity::Travel, Activity::Worship],
+            DayOfficer::Tru => &[Activity::MedicalTreatment, Activity::Worship, Activity::Travel],
+            DayOfficer::Man => &[Activity::Worship, Activity::OpeningBusiness, Activity::SigningContract],
+            DayOfficer::Binh => &[Activity::Construction, Activity::Wedding, Activity::MovingHouse],
+            DayOfficer::Dinh => &[Activity::Wedding, Activity::SigningContract, Activity::MovingHouse, Activity::OpeningBusiness],
+            DayOfficer::Chap => &[Activity::Construction, Activity::Planting],
+            DayOfficer::Pha => &[Activity::MedicalTreatment],
+            DayOfficer::Nguy => &[Activity::Worship],
+            DayOfficer::Thanh => &[Activity::Wedding, Activity::OpeningBusiness, Activity::MovingHouse,
+                Activity::Construction, Activity::SigningContract],
+            DayOfficer::Thu => &[Activity::OpeningBusiness, Activity::SigningContract],
+            DayOfficer::Khai => &[Activity::OpeningBusiness, Activity::Wedding, Activity::Travel, Activity::MovingHouse],
+            DayOfficer::Be => &[Activity::Burial],
+        }
+    }
+
+    pub fn bad_activities(&self) -> &'static [Activity] {
+        match self {
+            DayOfficer::Kien => &[Activity::Construction, Activity::Burial],
+            DayOfficer::Tru => &[Activity::Wedding, Activity::OpeningBusiness],
+            DayOfficer::Man => &[Activity::Planting, Activity::Burial, Activity::MedicalTreatment],
+            DayOfficer::Binh => &[Activity::Planting],
+            DayOfficer::Dinh => &[Activity::Travel, Activity::MedicalTreatment],
+            DayOfficer::Chap => &[Activity::MovingHouse, Activity::Travel, Activity::OpeningBusiness],
+            DayOfficer::Pha => &[Activity::Wedding, Activity::MovingHouse, Activity::OpeningBusiness,
+                Activity::SigningContract, Activity::Travel, Activity::Construction],
+            DayOfficer::Nguy => &[Activity::Travel, Activity::Construction],
+            DayOfficer::Thanh => &[],
+            DayOfficer::Thu => &[Activity::Burial, Activity::Travel, Activity::MedicalTreatment],
+            DayOfficer::Khai => &[Activity::Burial, Activity::Construction],
+            DayOfficer::Be => &[Activity::OpeningBusiness, Activity::Travel, Activity::MedicalTreatment, Activity::Wedding],
+        }
+    }
+}
+
+impl LunarDateTimeObject {
+    pub fn day_officer(&self) -> DayOfficer {
+        DayOfficer::from_datetime(self.gregorian_datetime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use super::*;
+
+    #[rstest]
+    // Tết Giáp Thìn, a Giáp Thìn day in the Dần month
+    #[case(2024, 2, 10, DayOfficer::Man)]
+    // Lập Xuân day repeats the officer of the day before
+    #[case(2024, 2, 3, DayOfficer::Thanh)]
+    #[case(2024, 2, 4, DayOfficer::Thanh)]
+    #[case(2024, 2, 5, DayOfficer::Thu)]
+    fn test_day_officer(#[case] yyyy: i32, #[case] mm: u32, #[case] dd: u32, #[case] expected: DayOfficer) {
+        let lo = LunarDateTimeObject::new_from_gregorian_datetime(dd, mm, yyyy, 12, 0, 7).unwrap();
+        assert_eq!(lo.day_officer(), expected);
+    }
+
+    #[test]
+    fn test_from_branches() {
+        assert_eq!(DayOfficer::from_branches(&EarthlyBranch::EB_TIGER, &EarthlyBranch::EB_TIGER), DayOfficer::Kien);
+        assert_eq!(DayOfficer::from_branches(&EarthlyBranch::EB_TIGER, &EarthlyBranch::EB_MONKEY), DayOfficer::Pha);
+        assert_eq!(DayOfficer::from_branches(&EarthlyBranch::EB_RAT, &EarthlyBranch::EB_PIG), DayOfficer::Be);
+        assert!(DayOfficer::Pha.bad_activities().contains(&Activity::Wedding));
+    }
+}
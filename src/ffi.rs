@@ -0,0 +1,326 @@
+// C API of the cdylib, see include/rlunar.h (regenerated with `cbindgen --config cbindgen.toml --output include/rlunar.h`).
+// Every function returns an RlunarStatus and writes its result through an out pointer, so no
+// allocation ever crosses the boundary; names are copied NUL-terminated into caller buffers.
+use std::ffi::{c_char, CStr};
+use chrono::{DateTime, FixedOffset, NaiveDate, TimeZone};
+use super::lunar_datetime::*;
+use super::lunisolar_datetime::*;
+use super::localization::*;
+
+/// Result code of every rlunar_* function.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum RlunarStatus {
+    Ok = 0,
+    NullPointer = 1,
+    /// Day, month, hour or minute out of range, or a leap month the year does not have.
+    InvalidDate = 2,
+    /// Year outside 1900..=2100.
+    OutOfRange = 3,
+    InvalidTimeZone = 4,
+    InvalidLocale = 5,
+    /// Stem, branch or month number out of range.
+    InvalidIndex = 6,
+    /// The name does not fit; nothing is written beyond the buffer.
+    BufferTooSmall = 7,
+}
+
+/// Gregorian date and time in the local time of the time zone given alongside.
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RlunarGregorian {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RlunarLunisolar {
+    pub year: i32,
+    pub month: u32,
+    pub day: u32,
+    pub leap: bool,
+    pub hour: u32,
+    pub minute: u32,
+}
+
+/// Stem 0 (Giáp) to 9 (Quý) and branch 0 (Tý) to 11 (Hợi).
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RlunarPillar {
+    pub stem: u8,
+    pub branch: u8,
+}
+
+#[repr(C)]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct RlunarPillars {
+    pub year: RlunarPillar,
+    pub month: RlunarPillar,
+    pub day: RlunarPillar,
+    pub hour: RlunarPillar,
+}
+
+impl From<&LunarFormat> for RlunarPillar {
+    fn from(pillar: &LunarFormat) -> RlunarPillar {
+        RlunarPillar { stem: pillar.stem.to_numeric() as u8, branch: pillar.branch.to_numeric() as u8 }
+    }
+}
+
+fn check_year(year: i32) -> Result<(), RlunarStatus> {
+    match (MIN_YEAR..=MAX_YEAR).contains(&year) {
+        true => Ok(()),
+        false => Err(RlunarStatus::OutOfRange),
+    }
+}
+
+fn offset(time_zone: f64) -> Result<FixedOffset, RlunarStatus> {
+    if !time_zone.is_finite() {
+        return Err(RlunarStatus::InvalidTimeZone);
+    }
+    FixedOffset::east_opt((time_zone * 3600.0).round() as i32).ok_or(RlunarStatus::InvalidTimeZone)
+}
+
+fn datetime(gregorian: &RlunarGregorian, time_zone: f64) -> Result<DateTime<FixedOffset>, RlunarStatus> {
+    check_year(gregorian.year)?;
+    let naive = NaiveDate::from_ymd_opt(gregorian.year, gregorian.month, gregorian.day)
+        .and_then(|date| date.and_hms_opt(gregorian.hour, gregorian.minute, 0))
+        .ok_or(RlunarStatus::InvalidDate)?;
+    offset(time_zone)?.from_local_datetime(&naive).single().ok_or(RlunarStatus::InvalidDate)
+}
+
+unsafe fn status<T>(result: Result<T, RlunarStatus>, out: *mut T) -> RlunarStatus {
+    match result {
+        Ok(value) => {
+            *out = value;
+            RlunarStatus::Ok
+        }
+        Err(status) => status,
+    }
+}
+
+unsafe fn locale(tag: *const c_char) -> Result<Locale, RlunarStatus> {
+    if tag.is_null() {
+        return Err(RlunarStatus::NullPointer);
+    }
+    CStr::from_ptr(tag).to_str().ok()
+        .and_then(Locale::from_tag)
+        .ok_or(RlunarStatus::InvalidLocale)
+}
+
+unsafe fn copy_name(name: &str, buffer: *mut c_char, buffer_len: usize) -> RlunarStatus {
+    if buffer.is_null() {
+        return RlunarStatus::NullPointer;
+    }
+    let bytes = name.as_bytes();
+    if bytes.len() + 1 > buffer_len {
+        return RlunarStatus::BufferTooSmall;
+    }
+    std::ptr::copy_nonoverlapping(bytes.as_ptr(), buffer as *mut u8, bytes.len());
+    *buffer.add(bytes.len()) = 0;
+    RlunarStatus::Ok
+}
+
+/// Lunisolar date of a Gregorian date and time, time_zone in hours east of UTC (7 for Vietnam).
+///
+/// # Safety
+/// `gregorian` must be readable and `out` writable, or null.
+#[no_mangle]
+pub unsafe extern "C" fn rlunar_gregorian_to_lunisolar(gregorian: *const RlunarGregorian, time_zone: f64,
+                                                       out: *mut RlunarLunisolar) -> RlunarStatus {
+    if gregorian.is_null() || out.is_null() {
+        return RlunarStatus::NullPointer;
+    }
+    let result = datetime(&*gregorian, time_zone).and_then(|datetime| {
+        let lunisolar = NaiveLunisolarDateTime::new_from_datetime(datetime).map_err(|_| RlunarStatus::OutOfRange)?;
+        Ok(RlunarLunisolar {
+            year: lunisolar.year_lunisolar as i32,
+            month: lunisolar.month_lunisolar,
+            day: lunisolar.day_lunisolar,
+            leap: lunisolar.leap_lunisolar,
+            hour: lunisolar.hour_lunisolar,
+            minute: lunisolar.minute_lunisolar,
+        })
+    });
+    status(result, out)
+}
+
+/// Gregorian date of a lunisolar date, the time of day carried over unchanged.
+///
+/// # Safety
+/// `lunisolar` must be readable and `out` writable, or null.
+#[no_mangle]
+pub unsafe extern "C" fn rlunar_lunisolar_to_gregorian(lunisolar: *const RlunarLunisolar, time_zone: f64,
+                                                       out: *mut RlunarGregorian) -> RlunarStatus {
+    if lunisolar.is_null() || out.is_null() {
+        return RlunarStatus::NullPointer;
+    }
+    let lunisolar = &*lunisolar;
+    let result = check_year(lunisolar.year).and_then(|_| {
+        offset(time_zone)?;
+        if lunisolar.hour > 23 || lunisolar.minute > 59 {
+            return Err(RlunarStatus::InvalidDate);
+        }
+        let date = NaiveLunisolarDateTime::lunisolar_to_gregorian(lunisolar.day, lunisolar.month, lunisolar.year, lunisolar.leap, time_zone)
+            .map_err(|_| RlunarStatus::InvalidDate)?;
+        Ok(RlunarGregorian {
+            year: chrono::Datelike::year(&date),
+            month: chrono::Datelike::month(&date),
+            day: chrono::Datelike::day(&date),
+            hour: lunisolar.hour,
+            minute: lunisolar.minute,
+        })
+    });
+    status(result, out)
+}
+
+/// Can Chi of the year, month, day and hour of a Gregorian date and time.
+///
+/// # Safety
+/// `gregorian` must be readable and `out` writable, or null.
+#[no_mangle]
+pub unsafe extern "C" fn rlunar_pillars(gregorian: *const RlunarGregorian, time_zone: f64,
+                                        out: *mut RlunarPillars) -> RlunarStatus {
+    if gregorian.is_null() || out.is_null() {
+        return RlunarStatus::NullPointer;
+    }
+    let result = datetime(&*gregorian, time_zone).and_then(|datetime| {
+        let lunar = NaiveLunarDateTime::new_from_datetime(datetime).map_err(|_| RlunarStatus::OutOfRange)?;
+        Ok(RlunarPillars {
+            year: RlunarPillar::from(&lunar.year_lunar),
+            month: RlunarPillar::from(&lunar.month_lunar),
+            day: RlunarPillar::from(&lunar.date_lunar),
+            hour: RlunarPillar::from(&lunar.hour_lunar),
+        })
+    });
+    status(result, out)
+}
+
+/// Name of stem 0..=9 in a locale tag such as "vi", "zh-Hans" or "en", UTF-8 and NUL-terminated.
+///
+/// # Safety
+/// `locale` must be a NUL-terminated string and `buffer` hold `buffer_len` bytes, or be null.
+#[no_mangle]
+pub unsafe extern "C" fn rlunar_stem_name(stem: u8, locale: *const c_char,
+                                          buffer: *mut c_char, buffer_len: usize) -> RlunarStatus {
+    let locale = match self::locale(locale) {
+        Ok(locale) => locale,
+        Err(status) => return status,
+    };
+    match HeavenlyStem::try_from(stem) {
+        Ok(stem) => copy_name(stem.localized_name(&locale), buffer, buffer_len),
+        Err(_) => RlunarStatus::InvalidIndex,
+    }
+}
+
+/// Name of branch 0..=11, see rlunar_stem_name.
+///
+/// # Safety
+/// `locale` must be a NUL-terminated string and `buffer` hold `buffer_len` bytes, or be null.
+#[no_mangle]
+pub unsafe extern "C" fn rlunar_branch_name(branch: u8, locale: *const c_char,
+                                            buffer: *mut c_char, buffer_len: usize) -> RlunarStatus {
+    let locale = match self::locale(locale) {
+        Ok(locale) => locale,
+        Err(status) => return status,
+    };
+    match EarthlyBranch::try_from(branch) {
+        Ok(branch) => copy_name(branch.localized_name(&locale), buffer, buffer_len),
+        Err(_) => RlunarStatus::InvalidIndex,
+    }
+}
+
+/// Name of lunisolar month 1..=12, such as "Tháng Giêng" or "闰二月", see rlunar_stem_name.
+///
+/// # Safety
+/// `locale` must be a NUL-terminated string and `buffer` hold `buffer_len` bytes, or be null.
+#[no_mangle]
+pub unsafe extern "C" fn rlunar_month_name(month: u32, leap: bool, locale: *const c_char,
+                                           buffer: *mut c_char, buffer_len: usize) -> RlunarStatus {
+    let locale = match self::locale(locale) {
+        Ok(locale) => locale,
+        Err(status) => return status,
+    };
+    match lunisolar_month_name(month, leap, &locale) {
+        Some(name) => copy_name(&name, buffer, buffer_len),
+        None => RlunarStatus::InvalidIndex,
+    }
+}
+
+/// Static English description of a status, never null.
+#[no_mangle]
+pub extern "C" fn rlunar_status_message(status: RlunarStatus) -> *const c_char {
+    let message: &CStr = match status {
+        RlunarStatus::Ok => c"ok",
+        RlunarStatus::NullPointer => c"null pointer argument",
+        RlunarStatus::InvalidDate => c"invalid date",
+        RlunarStatus::OutOfRange => c"year outside 1900..=2100",
+        RlunarStatus::InvalidTimeZone => c"invalid time zone",
+        RlunarStatus::InvalidLocale => c"unknown locale",
+        RlunarStatus::InvalidIndex => c"stem, branch or month out of range",
+        RlunarStatus::BufferTooSmall => c"buffer too small",
+    };
+    message.as_ptr()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let gregorian = RlunarGregorian { year: 2023, month: 3, day: 31, hour: 10, minute: 30 };
+        let mut lunisolar = RlunarLunisolar::default();
+        unsafe {
+            assert_eq!(rlunar_gregorian_to_lunisolar(&gregorian, 7.0, &mut lunisolar), RlunarStatus::Ok);
+            assert_eq!(lunisolar, RlunarLunisolar { year: 2023, month: 2, day: 10, leap: true, hour: 10, minute: 30 });
+            let mut back = RlunarGregorian::default();
+            assert_eq!(rlunar_lunisolar_to_gregorian(&lunisolar, 7.0, &mut back), RlunarStatus::Ok);
+            assert_eq!(back, gregorian);
+
+            let mut pillars = RlunarPillars::default();
+            assert_eq!(rlunar_pillars(&gregorian, 7.0, &mut pillars), RlunarStatus::Ok);
+            // Quý Mão
+            assert_eq!(pillars.year, RlunarPillar { stem: 9, branch: 3 });
+        }
+    }
+
+    #[test]
+    fn test_errors() {
+        let mut lunisolar = RlunarLunisolar::default();
+        let mut gregorian = RlunarGregorian::default();
+        unsafe {
+            let bad_day = RlunarGregorian { year: 2023, month: 2, day: 30, hour: 0, minute: 0 };
+            assert_eq!(rlunar_gregorian_to_lunisolar(&bad_day, 7.0, &mut lunisolar), RlunarStatus::InvalidDate);
+            let too_early = RlunarGregorian { year: 1800, month: 1, day: 1, hour: 0, minute: 0 };
+            assert_eq!(rlunar_gregorian_to_lunisolar(&too_early, 7.0, &mut lunisolar), RlunarStatus::OutOfRange);
+            let valid = RlunarGregorian { year: 2023, month: 3, day: 31, hour: 0, minute: 0 };
+            assert_eq!(rlunar_gregorian_to_lunisolar(&valid, f64::NAN, &mut lunisolar), RlunarStatus::InvalidTimeZone);
+            assert_eq!(rlunar_gregorian_to_lunisolar(std::ptr::null(), 7.0, &mut lunisolar), RlunarStatus::NullPointer);
+            // 2024 has no leap month
+            let no_leap = RlunarLunisolar { year: 2024, month: 2, day: 1, leap: true, hour: 0, minute: 0 };
+            assert_eq!(rlunar_lunisolar_to_gregorian(&no_leap, 7.0, &mut gregorian), RlunarStatus::InvalidDate);
+            assert_eq!(CStr::from_ptr(rlunar_status_message(RlunarStatus::OutOfRange)).to_str().unwrap(), "year outside 1900..=2100");
+        }
+    }
+
+    #[test]
+    fn test_names() {
+        let mut buffer = [0 as c_char; 32];
+        unsafe {
+            assert_eq!(rlunar_stem_name(0, c"vi".as_ptr(), buffer.as_mut_ptr(), buffer.len()), RlunarStatus::Ok);
+            assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str().unwrap(), "Giáp");
+            assert_eq!(rlunar_branch_name(4, c"zh-Hans".as_ptr(), buffer.as_mut_ptr(), buffer.len()), RlunarStatus::Ok);
+            assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str().unwrap(), "辰");
+            assert_eq!(rlunar_month_name(1, false, c"vi".as_ptr(), buffer.as_mut_ptr(), buffer.len()), RlunarStatus::Ok);
+            assert_eq!(CStr::from_ptr(buffer.as_ptr()).to_str().unwrap(), "Tháng Giêng");
+            assert_eq!(rlunar_stem_name(10, c"vi".as_ptr(), buffer.as_mut_ptr(), buffer.len()), RlunarStatus::InvalidIndex);
+            assert_eq!(rlunar_stem_name(0, c"xx".as_ptr(), buffer.as_mut_ptr(), buffer.len()), RlunarStatus::InvalidLocale);
+            // "Giáp" is five bytes in UTF-8
+            assert_eq!(rlunar_stem_name(0, c"vi".as_ptr(), buffer.as_mut_ptr(), 5), RlunarStatus::BufferTooSmall);
+        }
+    }
+}
@@ -0,0 +1,442 @@
+use std::fmt;
+use anyhow::{Result, anyhow, bail};
+use super::lunar_datetime::*;
+use super::localization::*;
+use super::lunisolar_datetime::*;
+use super::zodiac_and_element::*;
+use super::LunarDateTimeObject;
+
+// Format specifiers:
+// %d day, %m month, %L " nhuận" in a leap month, %Y year, %H hour, %M minute (zero padded),
+// %Cy %Cm %Cd %Ch Can Chi of the year, month, day and hour, %Z zodiac of the year, %% a literal %,
+// %Nd Chinese day (初一, 廿三), %Nm and %Tm simplified and traditional Chinese month (闰四月, 臘月),
+// %Vd Vietnamese day (mùng 1, 15), %Vm Vietnamese month (Tháng Giêng, Tháng Tư nhuận)
+#[derive(Clone, Debug, PartialEq)]
+enum Item {
+    Literal(char),
+    Day,
+    Month,
+    Leap,
+    Year,
+    Hour,
+    Minute,
+    YearPillar,
+    MonthPillar,
+    DayPillar,
+    HourPillar,
+    Zodiac,
+    ChineseDay,
+    ChineseMonth(Locale),
+    VietnameseDay,
+    VietnameseMonth,
+}
+
+const LEAP_SUFFIX: &str = " nhuận";
+
+fn tokenize(format: &str) -> Result<Vec<Item>> {
+    let mut items = Vec::new();
+    let mut chars = format.chars();
+    while let Some(c) = chars.next() {
+        if c != '%' {
+            items.push(Item::Literal(c));
+            continue;
+        }
+        let item = match chars.next() {
+            Some('d') => Item::Day,
+            Some('m') => Item::Month,
+            Some('L') => Item::Leap,
+            Some('Y') => Item::Year,
+            Some('H') => Item::Hour,
+            Some('M') => Item::Minute,
+            Some('Z') => Item::Zodiac,
+            Some('%') => Item::Literal('%'),
+            Some('C') => match chars.next() {
+                Some('y') => Item::YearPillar,
+                Some('m') => Item::MonthPillar,
+                Some('d') => Item::DayPillar,
+                Some('h') => Item::HourPillar,
+                other => bail!("unknown format specifier %C{}", other.map(String::from).unwrap_or_default()),
+            },
+            Some('N') => match chars.next() {
+                Some('d') => Item::ChineseDay,
+                Some('m') => Item::ChineseMonth(Locale::ZhHans),
+                other => bail!("unknown format specifier %N{}", other.map(String::from).unwrap_or_default()),
+            },
+            Some('T') => match chars.next() {
+                Some('m') => Item::ChineseMonth(Locale::ZhHant),
+                other => bail!("unknown format specifier %T{}", other.map(String::from).unwrap_or_default()),
+            },
+            Some('V') => match chars.next() {
+                Some('d') => Item::VietnameseDay,
+                Some('m') => Item::VietnameseMonth,
+                other => bail!("unknown format specifier %V{}", other.map(String::from).unwrap_or_default()),
+            },
+            other => bail!("unknown format specifier %{}", other.map(String::from).unwrap_or_default()),
+        };
+        items.push(item);
+    }
+    Ok(items)
+}
+
+const CHINESE_DIGITS: [&str; 10] = ["", "一", "二", "三", "四", "五", "六", "七", "八", "九"];
+
+// 初一..初十, 十一..十九, 二十, 廿一..廿九, 三十
+pub fn chinese_day_name(day: u32) -> Option<String> {
+    Some(match day {
+        1..=9 => format!("初{}", CHINESE_DIGITS[day as usize]),
+        10 => "初十".to_string(),
+        11..=19 => format!("十{}", CHINESE_DIGITS[day as usize - 10]),
+        20 => "二十".to_string(),
+        21..=29 => format!("廿{}", CHINESE_DIGITS[day as usize - 20]),
+        30 => "三十".to_string(),
+        _ => return None,
+    })
+}
+
+// Mùng (or mồng) for the first ten days, the plain number afterwards
+pub fn vietnamese_day_name(day: u32) -> String {
+    if (1..=10).contains(&day) { format!("mùng {}", day) } else { day.to_string() }
+}
+
+fn year_pillar(year: u32) -> LunarFormat {
+    LunarFormat::new(HeavenlyStem::lunar_y_from_lunisolar_y(year), EarthlyBranch::lunar_y_from_lunisolar_y(year))
+}
+
+fn month_pillar(month: u32, year: u32) -> LunarFormat {
+    LunarFormat::new(HeavenlyStem::lunar_m_from_lunisolar_ym(month, year), EarthlyBranch::lunar_m_from_lunisolar_m(month))
+}
+
+// Day and hour pillars depend on the gregorian date, so they need the lunar date time
+fn format_items(format: &str, lunisolar: &NaiveLunisolarDateTime, lunar: Option<&NaiveLunarDateTime>) -> Result<String> {
+    let mut result = String::new();
+    for item in tokenize(format)? {
+        let text = match item {
+            Item::Literal(c) => c.to_string(),
+            Item::Day => lunisolar.day_lunisolar.to_string(),
+            Item::Month => lunisolar.month_lunisolar.to_string(),
+            Item::Leap => if lunisolar.leap_lunisolar { LEAP_SUFFIX.to_string() } else { String::new() },
+            Item::Year => lunisolar.year_lunisolar.to_string(),
+            Item::Hour => format!("{:02}", lunisolar.hour_lunisolar),
+            Item::Minute => format!("{:02}", lunisolar.minute_lunisolar),
+            Item::YearPillar => year_pillar(lunisolar.year_lunisolar).to_string(),
+            Item::MonthPillar => month_pillar(lunisolar.month_lunisolar, lunisolar.year_lunisolar).to_string(),
+            Item::Zodiac => EarthlyBranch::lunar_y_from_lunisolar_y(lunisolar.year_lunisolar).as_zodiac().to_string(),
+            Item::DayPillar => lunar.ok_or_else(|| anyhow!("%Cd needs the gregorian date"))?.date_lunar.to_string(),
+            Item::HourPillar => lunar.ok_or_else(|| anyhow!("%Ch needs the gregorian date"))?.hour_lunar.to_string(),
+            Item::ChineseDay => chinese_day_name(lunisolar.day_lunisolar)
+                .ok_or_else(|| anyhow!("invalid day {}", lunisolar.day_lunisolar))?,
+            Item::ChineseMonth(locale) => lunisolar_month_name(lunisolar.month_lunisolar, lunisolar.leap_lunisolar, &locale)
+                .ok_or_else(|| anyhow!("invalid month {}", lunisolar.month_lunisolar))?,
+            Item::VietnameseDay => vietnamese_day_name(lunisolar.day_lunisolar),
+            Item::VietnameseMonth => lunisolar_month_name(lunisolar.month_lunisolar, lunisolar.leap_lunisolar, &Locale::Vi)
+                .ok_or_else(|| anyhow!("invalid month {}", lunisolar.month_lunisolar))?,
+        };
+        result.push_str(&text);
+    }
+    Ok(result)
+}
+
+// Case-insensitive prefix match, returning the remaining input
+fn strip_prefix_ignore_case<'a>(input: &'a str, prefix: &str) -> Option<&'a str> {
+    let head = input.get(..prefix.len())?;
+    if head.to_lowercase() == prefix.to_lowercase() { Some(&input[prefix.len()..]) } else { None }
+}
+
+fn parse_number(input: &str, max_digits: usize) -> Result<(u32, &str)> {
+    let digits = input.chars().take(max_digits).take_while(|c| c.is_ascii_digit()).count();
+    if digits == 0 {
+        bail!("expected a number at \"{}\"", input);
+    }
+    Ok((input[..digits].parse()?, &input[digits..]))
+}
+
+fn parse_chinese_day(input: &str) -> Result<(u32, &str)> {
+    (1..=30).find_map(|day| input.strip_prefix(chinese_day_name(day)?.as_str()).map(|rest| (day, rest)))
+        .ok_or_else(|| anyhow!("expected a Chinese day name at \"{}\"", input))
+}
+
+// Either script, so 腊月 and 臘月 are both read
+fn parse_chinese_month(input: &str) -> Result<(u32, bool, &str)> {
+    let (leap, input) = match input.strip_prefix('闰').or_else(|| input.strip_prefix('閏')) {
+        Some(rest) => (true, rest),
+        None => (false, input),
+    };
+    (1..=12).find_map(|month| [Locale::ZhHans, Locale::ZhHant].iter()
+        .filter_map(|locale| lunisolar_month_name(month, false, locale))
+        .find_map(|name| input.strip_prefix(name.as_str()))
+        .map(|rest| (month, leap, rest)))
+        .ok_or_else(|| anyhow!("expected a Chinese month name at \"{}\"", input))
+}
+
+// "mùng 1", "mồng 1", "rằm" for the 15th, or a plain number
+fn parse_vietnamese_day(input: &str) -> Result<(u32, &str)> {
+    if let Some(rest) = strip_prefix_ignore_case(input, "rằm") {
+        return Ok((15, rest));
+    }
+    let input = ["mùng ", "mồng "].iter()
+        .find_map(|prefix| strip_prefix_ignore_case(input, prefix))
+        .unwrap_or(input);
+    parse_number(input, 2)
+}
+
+fn parse_vietnamese_month(input: &str) -> Result<(u32, bool, &str)> {
+    let mut names: Vec<(u32, bool, String)> = (1..=12)
+        .flat_map(|month| [false, true].map(|leap| (month, leap, lunisolar_month_name(month, leap, &Locale::Vi).unwrap())))
+        .collect();
+    // Tháng Mười một before Tháng Mười, leap before regular
+    names.sort_by_key(|(_, _, name)| std::cmp::Reverse(name.len()));
+    names.into_iter()
+        .find_map(|(month, leap, name)| strip_prefix_ignore_case(input, &name).map(|rest| (month, leap, rest)))
+        .ok_or_else(|| anyhow!("expected a Vietnamese month name at \"{}\"", input))
+}
+
+fn parse_pillar(input: &str) -> Result<(LunarFormat, &str)> {
+    let (stem, rest) = HeavenlyStem::ALL.into_iter()
+        .find_map(|stem| strip_prefix_ignore_case(input, stem.vietnamese_name()).map(|rest| (stem, rest)))
+        .ok_or_else(|| anyhow!("expected a heavenly stem at \"{}\"", input))?;
+    let rest = rest.strip_prefix(' ').ok_or_else(|| anyhow!("expected a space after the heavenly stem"))?;
+    let (branch, rest) = EarthlyBranch::ALL.into_iter()
+        .find_map(|branch| strip_prefix_ignore_case(rest, branch.vietnamese_name()).map(|rest| (branch, rest)))
+        .ok_or_else(|| anyhow!("expected an earthly branch at \"{}\"", rest))?;
+    Ok((LunarFormat::new(stem, branch), rest))
+}
+
+impl NaiveLunisolarDateTime {
+    // Every specifier except %Cd and %Ch, which need the gregorian date
+    pub fn format(&self, format: &str) -> Result<String> {
+        format_items(format, self, None)
+    }
+
+    // Year, month and day are required, hour and minute default to 0.
+    // Parsed %Cy, %Cm and %Z have to agree with the year and month, %Cd and %Ch are only checked for well-formedness.
+    // The date has to exist in the calendar of time_zone: a leap month only in its year, day 30 only in a full month.
+    pub fn parse_from_str(input: &str, format: &str, time_zone: f64) -> Result<NaiveLunisolarDateTime> {
+        let (mut day, mut month, mut year) = (None, None, None);
+        let (mut hour, mut minute, mut leap) = (0, 0, false);
+        let (mut parsed_year_pillar, mut parsed_month_pillar, mut parsed_zodiac) = (None, None, None);
+        let mut rest = input;
+        for item in tokenize(format)? {
+            rest = match item {
+                Item::Literal(c) => rest.strip_prefix(c).ok_or_else(|| anyhow!("expected \"{}\" at \"{}\"", c, rest))?,
+                Item::Day => { let (value, rest) = parse_number(rest, 2)?; day = Some(value); rest }
+                Item::Month => { let (value, rest) = parse_number(rest, 2)?; month = Some(value); rest }
+                Item::Year => { let (value, rest) = parse_number(rest, 4)?; year = Some(value); rest }
+                Item::Hour => { let (value, rest) = parse_number(rest, 2)?; hour = value; rest }
+                Item::Minute => { let (value, rest) = parse_number(rest, 2)?; minute = value; rest }
+                Item::Leap => match strip_prefix_ignore_case(rest, LEAP_SUFFIX) {
+                    Some(rest) => { leap = true; rest }
+                    None => rest,
+                },
+                Item::YearPillar => { let (value, rest) = parse_pillar(rest)?; parsed_year_pillar = Some(value); rest }
+                Item::MonthPillar => { let (value, rest) = parse_pillar(rest)?; parsed_month_pillar = Some(value); rest }
+                Item::DayPillar | Item::HourPillar => parse_pillar(rest)?.1,
+                Item::ChineseDay => { let (value, rest) = parse_chinese_day(rest)?; day = Some(value); rest }
+                Item::VietnameseDay => { let (value, rest) = parse_vietnamese_day(rest)?; day = Some(value); rest }
+                Item::ChineseMonth(_) => {
+                    let (value, is_leap, rest) = parse_chinese_month(rest)?;
+                    month = Some(value);
+                    leap = is_leap;
+                    rest
+                }
+                Item::VietnameseMonth => {
+                    let (value, is_leap, rest) = parse_vietnamese_month(rest)?;
+                    month = Some(value);
+                    leap = is_leap;
+                    rest
+                }
+                Item::Zodiac => {
+                    let (zodiac, rest) = EarthlyBranch::ALL.into_iter().map(Zodiac::from)
+                        .find_map(|zodiac| strip_prefix_ignore_case(rest, zodiac.vietnamese_name()).map(|rest| (zodiac, rest)))
+                        .ok_or_else(|| anyhow!("expected a zodiac at \"{}\"", rest))?;
+                    parsed_zodiac = Some(zodiac);
+                    rest
+                }
+            };
+        }
+        if !rest.is_empty() {
+            bail!("unexpected trailing input \"{}\"", rest);
+        }
+
+        let day = day.ok_or_else(|| anyhow!("the format has no day"))?;
+        let month = month.ok_or_else(|| anyhow!("the format has no month"))?;
+        let year = year.ok_or_else(|| anyhow!("the format has no year"))?;
+        if !(1..=12).contains(&month) || !(1..=30).contains(&day) || hour > 23 || minute > 59 {
+            bail!("{} is out of range", input);
+        }
+        if let Some(pillar) = parsed_year_pillar.filter(|pillar| *pillar != year_pillar(year)) {
+            bail!("year {} is not {}", year, pillar);
+        }
+        if let Some(pillar) = parsed_month_pillar.filter(|pillar| *pillar != month_pillar(month, year)) {
+            bail!("month {}/{} is not {}", month, year, pillar);
+        }
+        if let Some(zodiac) = parsed_zodiac.filter(|zodiac| *zodiac != EarthlyBranch::lunar_y_from_lunisolar_y(year).as_zodiac()) {
+            bail!("year {} is not the year of the {}", year, zodiac);
+        }
+        let days_in_month = NaiveLunisolarDateTime::days_in_lunisolar_month(month, year as i32, leap, time_zone)?;
+        if day > days_in_month {
+            bail!("lunisolar month {}/{} has {} days", month, year, days_in_month);
+        }
+        Ok(NaiveLunisolarDateTime {
+            hour_lunisolar: hour,
+            minute_lunisolar: minute,
+            day_lunisolar: day,
+            month_lunisolar: month,
+            leap_lunisolar: leap,
+            year_lunisolar: year,
+        })
+    }
+}
+
+impl LunarDateTimeObject {
+    pub fn format(&self, format: &str) -> Result<String> {
+        format_items(format, &self.lunisolar_datetime, Some(&self.lunar_datetime))
+    }
+}
+
+impl fmt::Display for HeavenlyStem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.vietnamese_name())
+    }
+}
+
+impl fmt::Display for EarthlyBranch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.vietnamese_name())
+    }
+}
+
+impl fmt::Display for Zodiac {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.vietnamese_name())
+    }
+}
+
+impl fmt::Display for Element {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.vietnamese_name())
+    }
+}
+
+impl fmt::Display for YinYang {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.vietnamese_name())
+    }
+}
+
+// "Giáp Thìn"
+impl fmt::Display for LunarFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.stem, self.branch)
+    }
+}
+
+// "15/4 nhuận/2023 10:30"
+impl fmt::Display for NaiveLunisolarDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}/{}{}/{} {:02}:{:02}",
+               self.day_lunisolar,
+               self.month_lunisolar,
+               if self.leap_lunisolar { LEAP_SUFFIX } else { "" },
+               self.year_lunisolar,
+               self.hour_lunisolar,
+               self.minute_lunisolar)
+    }
+}
+
+// "giờ Giáp Tý, ngày Bính Dần, tháng Đinh Tỵ, năm Quý Mão"
+impl fmt::Display for NaiveLunarDateTime {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "giờ {}, ngày {}, tháng {}, năm {}", self.hour_lunar, self.date_lunar, self.month_lunar, self.year_lunar)
+    }
+}
+
+impl fmt::Display for LunarDateTimeObject {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} ({})", self.lunisolar_datetime, self.lunar_datetime)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use super::*;
+
+    #[test]
+    fn test_format() {
+        // 15 of the leap second month of Quý Mão
+        let object = LunarDateTimeObject::new_from_gregorian_datetime(5, 4, 2023, 9, 5, 7).unwrap();
+        assert_eq!(object.format("%d/%m%L/%Y (%Cy năm %Z)").unwrap(), "15/2 nhuận/2023 (Quý Mão năm Mèo)");
+        assert_eq!(object.format("%H:%M, 100%%").unwrap(), "09:05, 100%");
+        assert_eq!(object.format("%Cm").unwrap(), "Ất Mão");
+        assert_eq!(object.format("%Cd, %Ch").unwrap(), "Quý Tỵ, Đinh Tỵ");
+        assert_eq!(object.to_string(), "15/2 nhuận/2023 09:05 (giờ Đinh Tỵ, ngày Quý Tỵ, tháng Ất Mão, năm Quý Mão)");
+
+        assert!(object.lunisolar_datetime.format("%Cd").is_err());
+        assert!(object.format("%Q").is_err());
+        assert!(object.format("%C").is_err());
+    }
+
+    #[rstest]
+    #[case(1, "初一", "mùng 1")]
+    #[case(10, "初十", "mùng 10")]
+    #[case(11, "十一", "11")]
+    #[case(15, "十五", "15")]
+    #[case(20, "二十", "20")]
+    #[case(23, "廿三", "23")]
+    #[case(30, "三十", "30")]
+    fn test_day_name(#[case] day: u32, #[case] chinese: &str, #[case] vietnamese: &str) {
+        assert_eq!(chinese_day_name(day).unwrap(), chinese);
+        assert_eq!(vietnamese_day_name(day), vietnamese);
+        let parsed = NaiveLunisolarDateTime::parse_from_str(&format!("2023年{}腊月", chinese), "%Y年%Nd%Nm", 7.0).unwrap();
+        assert_eq!(parsed.day_lunisolar, day);
+    }
+
+    #[test]
+    fn test_traditional_format() {
+        let parsed = NaiveLunisolarDateTime::parse_from_str("2023 闰二月初一", "%Y %Nm%Nd", 7.0).unwrap();
+        assert_eq!((parsed.day_lunisolar, parsed.month_lunisolar, parsed.leap_lunisolar), (1, 2, true));
+        assert_eq!(parsed.format("%Nm%Nd").unwrap(), "闰二月初一");
+        assert_eq!(parsed.format("%Tm%Nd").unwrap(), "閏二月初一");
+        assert_eq!(parsed.format("%Vd %Vm").unwrap(), "mùng 1 Tháng Hai nhuận");
+
+        let parsed = NaiveLunisolarDateTime::parse_from_str("臘月廿三 2024", "%Tm%Nd %Y", 7.0).unwrap();
+        assert_eq!((parsed.day_lunisolar, parsed.month_lunisolar), (23, 12));
+        let parsed = NaiveLunisolarDateTime::parse_from_str("mồng 5 tháng mười một 2024", "%Vd %Vm %Y", 7.0).unwrap();
+        assert_eq!((parsed.day_lunisolar, parsed.month_lunisolar, parsed.leap_lunisolar), (5, 11, false));
+        let parsed = NaiveLunisolarDateTime::parse_from_str("Rằm Tháng Giêng 2024", "%Vd %Vm %Y", 7.0).unwrap();
+        assert_eq!((parsed.day_lunisolar, parsed.month_lunisolar), (15, 1));
+
+        assert!(chinese_day_name(31).is_none());
+        assert!(NaiveLunisolarDateTime::parse_from_str("2024 十三月初一", "%Y %Nm%Nd", 7.0).is_err());
+    }
+
+    #[test]
+    fn test_display() {
+        assert_eq!(HeavenlyStem::HS_GIAP.to_string(), "Giáp");
+        assert_eq!(EarthlyBranch::EB_SNAKE.to_string(), "Tỵ");
+        assert_eq!(LunarFormat::new(HeavenlyStem::HS_GIAP, EarthlyBranch::EB_DRAGON).to_string(), "Giáp Thìn");
+        assert_eq!(Zodiac::Cat.to_string(), "Mèo");
+        assert_eq!(Element::Fire.to_string(), "Hỏa");
+        assert_eq!(YinYang::Yang.to_string(), "Dương");
+    }
+
+    #[test]
+    fn test_parse_from_str() {
+        let parsed = NaiveLunisolarDateTime::parse_from_str("15/2 nhuận/2023 (Quý Mão năm Mèo)", "%d/%m%L/%Y (%Cy năm %Z)", 7.0).unwrap();
+        assert_eq!((parsed.day_lunisolar, parsed.month_lunisolar, parsed.leap_lunisolar, parsed.year_lunisolar), (15, 2, true, 2023));
+
+        let parsed = NaiveLunisolarDateTime::parse_from_str("1/1/2024 07:30", "%d/%m%L/%Y %H:%M", 7.0).unwrap();
+        assert!(!parsed.leap_lunisolar);
+        assert_eq!((parsed.hour_lunisolar, parsed.minute_lunisolar), (7, 30));
+        assert_eq!(parsed.format("%d/%m%L/%Y %H:%M").unwrap(), "1/1/2024 07:30");
+
+        assert!(NaiveLunisolarDateTime::parse_from_str("15/2/2023 (Giáp Thìn)", "%d/%m/%Y (%Cy)", 7.0).is_err());
+        assert!(NaiveLunisolarDateTime::parse_from_str("15/2/2023", "%d/%m", 7.0).is_err());
+        assert!(NaiveLunisolarDateTime::parse_from_str("15/13/2023", "%d/%m/%Y", 7.0).is_err());
+        assert!(NaiveLunisolarDateTime::parse_from_str("15/2", "%d/%m", 7.0).is_err());
+        assert!(NaiveLunisolarDateTime::parse_from_str("1/2 nhuận/2024", "%d/%m%L/%Y", 7.0).is_err());
+        assert!(NaiveLunisolarDateTime::parse_from_str("30/12/2024", "%d/%m/%Y", 7.0).is_err());
+        assert!(NaiveLunisolarDateTime::parse_from_str("30/12/2023", "%d/%m/%Y", 7.0).is_ok());
+        assert!(NaiveLunisolarDateTime::parse_from_str("1/1/3000", "%d/%m/%Y", 7.0).is_err());
+    }
+}
@@ -0,0 +1,220 @@
+use anyhow::{Result, bail};
+use chrono::{Datelike, NaiveDate};
+use super::lunisolar_datetime::*;
+use super::solar_term::*;
+use super::calendar_system::*;
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum HolidayRule {
+    // A day of a regular (non-leap) lunisolar month; day 30 falls back to the 29th in short months
+    Lunisolar { month: u32, day: u32 },
+    // Last day of a lunisolar month, the 29th or the 30th depending on the year
+    LunisolarMonthEnd { month: u32 },
+    SolarTerm(SolarTerm),
+    // Eve of a solar term, like Setsubun before Risshun
+    DayBeforeSolarTerm(SolarTerm),
+    Gregorian { month: u32, day: u32 },
+}
+
+impl HolidayRule {
+    fn lunisolar_date(month: u32, day: Option<u32>, lunisolar_year: i32, time_zone: f64) -> Result<NaiveDate> {
+        let days_in_month = NaiveLunisolarDateTime::days_in_lunisolar_month(month, lunisolar_year, false, time_zone)?;
+        let day = day.map_or(days_in_month, |day| day.min(days_in_month));
+        NaiveLunisolarDateTime::lunisolar_to_gregorian(day, month, lunisolar_year, false, time_zone)
+    }
+
+    // Dates in the gregorian year the rule falls on; lunisolar rules may fall twice or not at all
+    pub fn dates_in_year(&self, year: i32, time_zone: f64) -> Result<Vec<NaiveDate>> {
+        let lunisolar = |month: u32, day: Option<u32>| -> Result<Vec<NaiveDate>> {
+            let mut dates = Vec::new();
+            for lunisolar_year in [year - 1, year] {
+                if lunisolar_year < MIN_YEAR {
+                    continue;
+                }
+                let date = HolidayRule::lunisolar_date(month, day, lunisolar_year, time_zone)?;
+                if date.year() == year {
+                    dates.push(date);
+                }
+            }
+            Ok(dates)
+        };
+
+        match self {
+            HolidayRule::Lunisolar { month, day } => lunisolar(*month, Some(*day)),
+            HolidayRule::LunisolarMonthEnd { month } => lunisolar(*month, None),
+            HolidayRule::SolarTerm(term) => Ok(term.start_date_in_year(year, time_zone)?.into_iter().collect()),
+            HolidayRule::DayBeforeSolarTerm(term) => Ok(term.start_date_in_year(year, time_zone)?
+                .and_then(|date| date.pred_opt())
+                .filter(|date| date.year() == year)
+                .into_iter().collect()),
+            HolidayRule::Gregorian { month, day } => match NaiveDate::from_ymd_opt(year, *month, *day) {
+                Some(date) => Ok(vec![date]),
+                None => bail!("invalid gregorian holiday {}/{}", day, month),
+            },
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct Holiday {
+    pub name: &'static str,
+    pub rule: HolidayRule,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HolidayOccurrence {
+    pub holiday: Holiday,
+    pub date: NaiveDate,
+}
+
+pub const VIETNAMESE_HOLIDAYS: [Holiday; 15] = [
+    Holiday { name: "Tết Dương lịch", rule: HolidayRule::Gregorian { month: 1, day: 1 } },
+    Holiday { name: "Tết Nguyên Đán", rule: HolidayRule::Lunisolar { month: 1, day: 1 } },
+    Holiday { name: "Rằm tháng Giêng", rule: HolidayRule::Lunisolar { month: 1, day: 15 } },
+    Holiday { name: "Tết Hàn Thực", rule: HolidayRule::Lunisolar { month: 3, day: 3 } },
+    Holiday { name: "Giỗ Tổ Hùng Vương", rule: HolidayRule::Lunisolar { month: 3, day: 10 } },
+    Holiday { name: "Thanh Minh", rule: HolidayRule::SolarTerm(SolarTerm::ThanhMinh) },
+    Holiday { name: "Ngày Giải phóng miền Nam", rule: HolidayRule::Gregorian { month: 4, day: 30 } },
+    Holiday { name: "Ngày Quốc tế Lao động", rule: HolidayRule::Gregorian { month: 5, day: 1 } },
+    Holiday { name: "Phật Đản", rule: HolidayRule::Lunisolar { month: 4, day: 15 } },
+    Holiday { name: "Tết Đoan Ngọ", rule: HolidayRule::Lunisolar { month: 5, day: 5 } },
+    Holiday { name: "Vu Lan", rule: HolidayRule::Lunisolar { month: 7, day: 15 } },
+    Holiday { name: "Tết Trung Thu", rule: HolidayRule::Lunisolar { month: 8, day: 15 } },
+    Holiday { name: "Quốc khánh", rule: HolidayRule::Gregorian { month: 9, day: 2 } },
+    Holiday { name: "Ông Công Ông Táo", rule: HolidayRule::Lunisolar { month: 12, day: 23 } },
+    Holiday { name: "Tất Niên", rule: HolidayRule::LunisolarMonthEnd { month: 12 } },
+];
+
+pub const CHINESE_FESTIVALS: [Holiday; 12] = [
+    Holiday { name: "Spring Festival", rule: HolidayRule::Lunisolar { month: 1, day: 1 } },
+    Holiday { name: "Lantern Festival", rule: HolidayRule::Lunisolar { month: 1, day: 15 } },
+    Holiday { name: "Qingming Festival", rule: HolidayRule::SolarTerm(SolarTerm::ThanhMinh) },
+    Holiday { name: "Dragon Boat Festival", rule: HolidayRule::Lunisolar { month: 5, day: 5 } },
+    Holiday { name: "Qixi Festival", rule: HolidayRule::Lunisolar { month: 7, day: 7 } },
+    Holiday { name: "Ghost Festival", rule: HolidayRule::Lunisolar { month: 7, day: 15 } },
+    Holiday { name: "Mid-Autumn Festival", rule: HolidayRule::Lunisolar { month: 8, day: 15 } },
+    Holiday { name: "Double Ninth Festival", rule: HolidayRule::Lunisolar { month: 9, day: 9 } },
+    Holiday { name: "Dongzhi Festival", rule: HolidayRule::SolarTerm(SolarTerm::DongChi) },
+    Holiday { name: "Laba Festival", rule: HolidayRule::Lunisolar { month: 12, day: 8 } },
+    Holiday { name: "Little New Year", rule: HolidayRule::Lunisolar { month: 12, day: 23 } },
+    Holiday { name: "New Year's Eve", rule: HolidayRule::LunisolarMonthEnd { month: 12 } },
+];
+
+pub const KOREAN_FESTIVALS: [Holiday; 7] = [
+    Holiday { name: "Seollal", rule: HolidayRule::Lunisolar { month: 1, day: 1 } },
+    Holiday { name: "Jeongwol Daeboreum", rule: HolidayRule::Lunisolar { month: 1, day: 15 } },
+    Holiday { name: "Buddha's Birthday", rule: HolidayRule::Lunisolar { month: 4, day: 8 } },
+    Holiday { name: "Dano", rule: HolidayRule::Lunisolar { month: 5, day: 5 } },
+    Holiday { name: "Chilseok", rule: HolidayRule::Lunisolar { month: 7, day: 7 } },
+    Holiday { name: "Chuseok", rule: HolidayRule::Lunisolar { month: 8, day: 15 } },
+    Holiday { name: "Dongji", rule: HolidayRule::SolarTerm(SolarTerm::DongChi) },
+];
+
+pub const JAPANESE_FESTIVALS: [Holiday; 9] = [
+    Holiday { name: "Kyū Shōgatsu", rule: HolidayRule::Lunisolar { month: 1, day: 1 } },
+    Holiday { name: "Setsubun", rule: HolidayRule::DayBeforeSolarTerm(SolarTerm::LapXuan) },
+    Holiday { name: "Shunbun no Hi", rule: HolidayRule::SolarTerm(SolarTerm::XuanPhan) },
+    Holiday { name: "Kyū Tanabata", rule: HolidayRule::Lunisolar { month: 7, day: 7 } },
+    Holiday { name: "Jūgoya", rule: HolidayRule::Lunisolar { month: 8, day: 15 } },
+    Holiday { name: "Shūbun no Hi", rule: HolidayRule::SolarTerm(SolarTerm::ThuPhan) },
+    Holiday { name: "Jūsan'ya", rule: HolidayRule::Lunisolar { month: 9, day: 13 } },
+    Holiday { name: "Tōji", rule: HolidayRule::SolarTerm(SolarTerm::DongChi) },
+    Holiday { name: "Ōmisoka", rule: HolidayRule::Gregorian { month: 12, day: 31 } },
+];
+
+impl CalendarSystem {
+    pub fn festivals(&self) -> &'static [Holiday] {
+        match self {
+            CalendarSystem::Vietnamese => { &VIETNAMESE_HOLIDAYS }
+            CalendarSystem::Chinese => { &CHINESE_FESTIVALS }
+            CalendarSystem::Korean => { &KOREAN_FESTIVALS }
+            CalendarSystem::Japanese => { &JAPANESE_FESTIVALS }
+        }
+    }
+}
+
+// Festivals of the tradition falling in the gregorian year, reckoned at its own meridian
+pub fn festivals_in_year(system: &CalendarSystem, year: i32) -> Result<Vec<HolidayOccurrence>> {
+    let mut result = Vec::new();
+    for holiday in system.festivals().iter() {
+        for date in holiday.rule.dates_in_year(year, system.time_zone())? {
+            result.push(HolidayOccurrence { holiday: holiday.clone(), date });
+        }
+    }
+    result.sort_by_key(|occurrence| occurrence.date);
+    Ok(result)
+}
+
+pub fn festivals_on(system: &CalendarSystem, date: NaiveDate) -> Result<Vec<HolidayOccurrence>> {
+    Ok(festivals_in_year(system, date.year())?.into_iter()
+        .filter(|occurrence| occurrence.date == date)
+        .collect())
+}
+
+pub fn holidays_in_year(year: i32) -> Result<Vec<HolidayOccurrence>> {
+    festivals_in_year(&CalendarSystem::Vietnamese, year)
+}
+
+pub fn holidays_on(date: NaiveDate) -> Result<Vec<HolidayOccurrence>> {
+    festivals_on(&CalendarSystem::Vietnamese, date)
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use super::*;
+
+    fn names(occurrences: &[HolidayOccurrence]) -> Vec<&str> {
+        occurrences.iter().map(|o| o.holiday.name).collect()
+    }
+
+    #[rstest]
+    #[case(2024, 2, 10, vec!["Tết Nguyên Đán"])]
+    #[case(2024, 2, 9, vec!["Tất Niên"])]
+    #[case(2024, 4, 4, vec!["Thanh Minh"])]
+    #[case(2024, 4, 18, vec!["Giỗ Tổ Hùng Vương"])]
+    #[case(2024, 9, 17, vec!["Tết Trung Thu"])]
+    #[case(2024, 9, 2, vec!["Quốc khánh"])]
+    #[case(2024, 3, 1, vec![])]
+    // Giáp Thìn has a 29-day twelfth month, Tất Niên falls on the 29th
+    #[case(2025, 1, 28, vec!["Tất Niên"])]
+    fn test_holidays_on(#[case] yyyy: i32, #[case] mm: u32, #[case] dd: u32, #[case] expected: Vec<&str>) {
+        let holidays = holidays_on(NaiveDate::from_ymd_opt(yyyy, mm, dd).unwrap()).unwrap();
+        assert_eq!(names(&holidays), expected);
+    }
+
+    #[test]
+    fn test_holidays_in_year() {
+        let holidays = holidays_in_year(2024).unwrap();
+        assert_eq!(holidays.first().unwrap().holiday.name, "Tết Dương lịch");
+        // Ông Công Ông Táo of Quý Mão falls in 2024, the one of Giáp Thìn in January 2025
+        let tao_quan: Vec<NaiveDate> = holidays.iter()
+            .filter(|o| o.holiday.name == "Ông Công Ông Táo")
+            .map(|o| o.date)
+            .collect();
+        assert_eq!(tao_quan, vec![NaiveDate::from_ymd_opt(2024, 2, 2).unwrap()]);
+        for pair in holidays.windows(2) {
+            assert!(pair[0].date <= pair[1].date);
+        }
+    }
+
+    #[test]
+    fn test_month_end_fallback() {
+        let rule = HolidayRule::Lunisolar { month: 12, day: 30 };
+        assert_eq!(rule.dates_in_year(2025, CalendarSystem::Vietnamese.time_zone()).unwrap(), vec![NaiveDate::from_ymd_opt(2025, 1, 28).unwrap()]);
+        assert_eq!(rule.dates_in_year(2024, CalendarSystem::Vietnamese.time_zone()).unwrap(), vec![NaiveDate::from_ymd_opt(2024, 2, 9).unwrap()]);
+    }
+
+    #[rstest]
+    // Vietnam celebrated Tết Đinh Hợi a day before China
+    #[case(CalendarSystem::Vietnamese, 2007, 2, 17, "Tết Nguyên Đán")]
+    #[case(CalendarSystem::Chinese, 2007, 2, 18, "Spring Festival")]
+    #[case(CalendarSystem::Chinese, 2024, 6, 10, "Dragon Boat Festival")]
+    #[case(CalendarSystem::Chinese, 2024, 12, 21, "Dongzhi Festival")]
+    #[case(CalendarSystem::Korean, 2024, 9, 17, "Chuseok")]
+    #[case(CalendarSystem::Japanese, 2024, 2, 3, "Setsubun")]
+    fn test_festivals_on(#[case] system: CalendarSystem, #[case] yyyy: i32, #[case] mm: u32, #[case] dd: u32, #[case] expected: &str) {
+        let festivals = festivals_on(&system, NaiveDate::from_ymd_opt(yyyy, mm, dd).unwrap()).unwrap();
+        assert_eq!(names(&festivals), vec![expected]);
+    }
+}
@@ -0,0 +1,177 @@
+use anyhow::{Result, anyhow, bail};
+use chrono::{Datelike, DateTime, FixedOffset, NaiveDate, TimeZone, Utc};
+use super::lunar_datetime::*;
+use super::lunisolar_datetime::*;
+use super::recurrence::*;
+use super::LunarDateTimeObject;
+
+const PRODID: &str = "-//rlunar//Lunisolar calendar//VI";
+// RFC 5545 section 3.1: content lines are folded after 75 octets
+const MAX_LINE_OCTETS: usize = 75;
+
+// A lunisolar-recurring event (giỗ, birthday, festival) to expand into dated VEVENTs
+#[derive(Clone, Debug, PartialEq)]
+pub struct IcsEvent {
+    // Prefix of the UID of every expanded VEVENT, stable across exports
+    pub uid: String,
+    pub summary: String,
+    pub description: Option<String>,
+    pub recurrence: LunisolarRecurrence,
+}
+
+impl IcsEvent {
+    pub fn new(uid: &str, summary: &str, recurrence: LunisolarRecurrence) -> IcsEvent {
+        IcsEvent {
+            uid: uid.to_string(),
+            summary: summary.to_string(),
+            description: None,
+            recurrence,
+        }
+    }
+
+    // Occurrences between the two dates, both included, as far as the supported years reach
+    pub fn occurrences(&self, from: NaiveDate, to: NaiveDate) -> Result<Vec<NaiveDate>> {
+        let mut result = Vec::new();
+        for year in (from.year() - 1).max(MIN_YEAR)..=to.year().min(MAX_YEAR) {
+            if let Some(date) = self.recurrence.occurrence_in_lunisolar_year(year)? {
+                if date >= from && date <= to {
+                    result.push(date);
+                }
+            }
+        }
+        Ok(result)
+    }
+}
+
+// e.g. "15/8/2024 âm lịch, ngày Giáp Thân tháng Quý Dậu năm Giáp Thìn"
+const LABEL_FORMAT: &str = "%d/%m%L/%Y âm lịch, ngày %Cd tháng %Cm năm %Cy";
+
+// TEXT value escaping of RFC 5545 section 3.3.11
+fn escape_text(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace("\r\n", "\\n")
+        .replace('\n', "\\n")
+}
+
+// Folds a content line without splitting a UTF-8 sequence, terminating every physical line with CRLF
+fn fold_line(line: &str, output: &mut String) {
+    let mut octets = 0;
+    for c in line.chars() {
+        if octets + c.len_utf8() > MAX_LINE_OCTETS {
+            output.push_str("\r\n ");
+            octets = 1;
+        }
+        output.push(c);
+        octets += c.len_utf8();
+    }
+    output.push_str("\r\n");
+}
+
+fn format_date(date: NaiveDate) -> String {
+    date.format("%Y%m%d").to_string()
+}
+
+// Pillars are taken at noon local time, the offset kept to the second for zones such as +5.5
+fn lunar_at_noon(date: NaiveDate, time_zone: f64) -> Result<LunarDateTimeObject> {
+    let offset = FixedOffset::east_opt((time_zone * 3600.0).round() as i32)
+        .filter(|_| time_zone.is_finite())
+        .ok_or_else(|| anyhow!("invalid time zone {}", time_zone))?;
+    let gregorian_datetime = offset.from_local_datetime(&date.and_hms_opt(12, 0, 0).unwrap()).unwrap();
+    Ok(LunarDateTimeObject {
+        lunisolar_datetime: NaiveLunisolarDateTime::new_from_datetime(gregorian_datetime)?,
+        lunar_datetime: NaiveLunarDateTime::new_from_datetime(gregorian_datetime)?,
+        gregorian_datetime,
+    })
+}
+
+pub fn export_with_timestamp(events: &[IcsEvent], from: NaiveDate, to: NaiveDate, timestamp: DateTime<Utc>) -> Result<String> {
+    if from > to {
+        bail!("date range starts after it ends");
+    }
+    let mut lines = vec![
+        "BEGIN:VCALENDAR".to_string(),
+        "VERSION:2.0".to_string(),
+        format!("PRODID:{}", PRODID),
+        "CALSCALE:GREGORIAN".to_string(),
+    ];
+    for event in events {
+        for date in event.occurrences(from, to)? {
+            let label = lunar_at_noon(date, event.recurrence.time_zone)?.format(LABEL_FORMAT)?;
+            lines.push("BEGIN:VEVENT".to_string());
+            lines.push(format!("UID:{}-{}@rlunar", event.uid, format_date(date)));
+            lines.push(format!("DTSTAMP:{}", timestamp.format("%Y%m%dT%H%M%SZ")));
+            lines.push(format!("DTSTART;VALUE=DATE:{}", format_date(date)));
+            lines.push(format!("DTEND;VALUE=DATE:{}", format_date(date.succ_opt().unwrap())));
+            lines.push(format!("SUMMARY:{}", escape_text(&format!("{} ({})", event.summary, label))));
+            if let Some(description) = &event.description {
+                lines.push(format!("DESCRIPTION:{}", escape_text(description)));
+            }
+            lines.push("TRANSP:TRANSPARENT".to_string());
+            lines.push("END:VEVENT".to_string());
+        }
+    }
+    lines.push("END:VCALENDAR".to_string());
+
+    let mut output = String::new();
+    for line in lines {
+        fold_line(&line, &mut output);
+    }
+    Ok(output)
+}
+
+// Expands the events into a VCALENDAR covering the date range, both ends included
+pub fn export(events: &[IcsEvent], from: NaiveDate, to: NaiveDate) -> Result<String> {
+    export_with_timestamp(events, from, to, Utc::now())
+}
+
+#[cfg(test)]
+mod tests {
+    use chrono::TimeZone;
+    use super::*;
+
+    fn ymd(yyyy: i32, mm: u32, dd: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(yyyy, mm, dd).unwrap()
+    }
+
+    #[test]
+    fn test_export() {
+        let mut event = IcsEvent::new("gio-ong-noi", "Giỗ ông nội", LunisolarRecurrence::new(15, 8, false, 7.0));
+        event.description = Some("Làm cỗ, mời họ hàng; nhớ mua hoa".to_string());
+        let timestamp = Utc.with_ymd_and_hms(2024, 1, 1, 0, 0, 0).unwrap();
+        let ics = export_with_timestamp(&[event], ymd(2023, 1, 1), ymd(2024, 12, 31), timestamp).unwrap();
+
+        assert!(ics.starts_with("BEGIN:VCALENDAR\r\nVERSION:2.0\r\n"));
+        assert!(ics.ends_with("END:VCALENDAR\r\n"));
+        assert_eq!(ics.matches("BEGIN:VEVENT").count(), 2);
+        assert!(ics.contains("UID:gio-ong-noi-20230929@rlunar\r\n"));
+        assert!(ics.contains("DTSTART;VALUE=DATE:20240917\r\nDTEND;VALUE=DATE:20240918\r\n"));
+        assert!(ics.contains("DTSTAMP:20240101T000000Z\r\n"));
+        assert!(ics.contains("DESCRIPTION:Làm cỗ\\, mời họ hàng\\; nhớ mua hoa"));
+
+        let unfolded = ics.replace("\r\n ", "");
+        assert!(unfolded.contains("SUMMARY:Giỗ ông nội (15/8/2024 âm lịch\\, ngày Giáp Thân tháng Quý Dậu năm Giáp Thìn)\r\n"));
+        for line in ics.split("\r\n") {
+            assert!(line.len() <= MAX_LINE_OCTETS);
+        }
+    }
+
+    #[test]
+    fn test_export_range() {
+        let event = IcsEvent::new("tet", "Tết", LunisolarRecurrence::new(1, 1, false, 7.0));
+        assert!(export(std::slice::from_ref(&event), ymd(2024, 3, 1), ymd(2024, 2, 1)).is_err());
+        assert_eq!(event.occurrences(ymd(2024, 3, 1), ymd(2025, 1, 28)).unwrap(), vec![]);
+        assert_eq!(event.occurrences(ymd(2024, 1, 1), ymd(2025, 12, 31)).unwrap(), vec![ymd(2024, 2, 10), ymd(2025, 1, 29)]);
+        assert_eq!(event.occurrences(ymd(1900, 1, 1), ymd(1902, 1, 1)).unwrap().len(), 2);
+        assert_eq!(event.occurrences(ymd(2100, 1, 1), ymd(2101, 12, 31)).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_fractional_time_zone() {
+        let lunar = lunar_at_noon(ymd(2024, 9, 17), 5.5).unwrap();
+        assert_eq!(lunar.gregorian_datetime.offset().local_minus_utc(), 5 * 3600 + 1800);
+        assert_eq!(lunar.format("%d/%m/%Y %Cd").unwrap(), "15/8/2024 Giáp Thân");
+        assert!(lunar_at_noon(ymd(2024, 9, 17), f64::NAN).is_err());
+    }
+}
@@ -2,33 +2,98 @@ mod lunar_datetime;
 mod lunisolar_datetime;
 mod zodiac_and_element;
 mod localization;
+mod compatibility;
+mod twelve_spirit;
+mod solar_term;
+mod activity;
+mod day_officer;
+mod lunar_mansion;
+mod star;
+mod taboo_day;
+mod date_finder;
+mod calendar_system;
+mod holiday;
+mod recurrence;
+mod format;
+mod text_parser;
+mod calendar_grid;
+#[cfg(feature = "ics")]
+pub mod ics;
+pub mod rscale;
+#[cfg(feature = "serde")]
+pub mod serde_support;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+#[cfg(feature = "wasm")]
+pub mod wasm;
 
-use lunar_datetime::*;
+pub use lunar_datetime::*;
+pub use localization::*;
+pub use lunisolar_datetime::*;
+pub use zodiac_and_element::*;
+pub use compatibility::*;
+pub use twelve_spirit::*;
+pub use solar_term::*;
+pub use activity::*;
+pub use day_officer::*;
+pub use lunar_mansion::*;
+pub use star::*;
+pub use taboo_day::*;
+pub use date_finder::*;
+pub use calendar_system::*;
+pub use holiday::*;
+pub use recurrence::*;
+pub use format::*;
+pub use text_parser::*;
+pub use calendar_grid::*;
 
-use lunisolar_datetime::*;
 use chrono::prelude::*;
 use chrono::{NaiveTime, NaiveDate};
-use anyhow::Result;
+use anyhow::{anyhow, Result};
 
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LunarDateTimeObject {
+    #[cfg_attr(feature = "serde", serde(rename = "lunisolar"))]
     pub lunisolar_datetime: NaiveLunisolarDateTime,
+    #[cfg_attr(feature = "serde", serde(rename = "lunar"))]
     pub lunar_datetime: NaiveLunarDateTime,
+    #[cfg_attr(feature = "serde", serde(rename = "gregorian"))]
     pub gregorian_datetime: DateTime<FixedOffset>
 }
 
+// By instant, ties between offsets broken by the local calendar values
+impl Ord for LunarDateTimeObject {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        (self.gregorian_datetime, self.lunisolar_datetime, self.lunar_datetime)
+            .cmp(&(other.gregorian_datetime, other.lunisolar_datetime, other.lunar_datetime))
+    }
+}
+
+impl PartialOrd for LunarDateTimeObject {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl LunarDateTimeObject {
-    fn new_from_gregorian_datetime(dd: u32, mm:u32, yyyy:i32, hour:u32, min:u32, time_zone: i64) -> Result<LunarDateTimeObject>{
-        let date = NaiveDate::from_ymd_opt(yyyy, mm, dd).unwrap();
-        let time = NaiveTime::from_hms_opt(hour, min, 0).unwrap();
+    pub fn new_from_gregorian_datetime(dd: u32, mm:u32, yyyy:i32, hour:u32, min:u32, time_zone: i64) -> Result<LunarDateTimeObject>{
+        let date = NaiveDate::from_ymd_opt(yyyy, mm, dd)
+            .ok_or_else(|| anyhow!("invalid gregorian date {}-{}-{}", yyyy, mm, dd))?;
+        let time = NaiveTime::from_hms_opt(hour, min, 0)
+            .ok_or_else(|| anyhow!("invalid time {}:{}", hour, min))?;
         let naive_datetime = NaiveDateTime::new(date, time);
 
-        let offset = FixedOffset::east_opt(chrono::Duration::hours(time_zone).num_seconds() as i32).unwrap();
-        let gregorian_datetime = naive_datetime.and_local_timezone(offset).unwrap();
+        let offset = time_zone.checked_mul(3600)
+            .and_then(|seconds| i32::try_from(seconds).ok())
+            .and_then(FixedOffset::east_opt)
+            .ok_or_else(|| anyhow!("invalid time zone {}", time_zone))?;
+        let gregorian_datetime = naive_datetime.and_local_timezone(offset).single()
+            .ok_or_else(|| anyhow!("invalid local time {}", naive_datetime))?;
 
-        let lunisolar_datetime = NaiveLunisolarDateTime::new_from_datetime(gregorian_datetime.clone())?;
-        let lunar_datetime = NaiveLunarDateTime::new_from_datetime(gregorian_datetime.clone())?;
+        let lunisolar_datetime = NaiveLunisolarDateTime::new_from_datetime(gregorian_datetime)?;
+        let lunar_datetime = NaiveLunarDateTime::new_from_datetime(gregorian_datetime)?;
 
         Ok(LunarDateTimeObject {
             lunisolar_datetime,
@@ -40,6 +105,7 @@ impl LunarDateTimeObject {
 
 
 #[cfg(test)]
+#[allow(clippy::too_many_arguments)]
 mod tests {
     use rstest::rstest;
     use super::*;
@@ -64,4 +130,15 @@ mod tests {
         }
 
     }
+
+    #[rstest]
+    #[case(31, 2, 2024, 10, 0, 7)]
+    #[case(1, 1, 2024, 24, 0, 7)]
+    #[case(1, 1, 2024, 10, 60, 7)]
+    #[case(1, 1, 2024, 10, 0, 25)]
+    #[case(1, 1, 2024, 10, 0, i64::MAX)]
+    fn test_invalid_gregorian_datetime(#[case] dd: u32, #[case] mm: u32, #[case] yyyy: i32,
+                                       #[case] hour: u32, #[case] min: u32, #[case] time_zone: i64) {
+        assert!(LunarDateTimeObject::new_from_gregorian_datetime(dd, mm, yyyy, hour, min, time_zone).is_err());
+    }
 }
\ No newline at end of file
@@ -1,4 +1,10 @@
 use super::lunar_datetime::*;
+use super::lunisolar_datetime::*;
+use super::solar_term::*;
+use super::zodiac_and_element::*;
+use anyhow::{anyhow, Result};
+use std::str::FromStr;
+
 impl HeavenlyStem {
     pub const HS_GIAP: HeavenlyStem = HeavenlyStem::HS1;
     pub const HS_AT: HeavenlyStem = HeavenlyStem::HS2;
@@ -26,4 +32,447 @@ impl EarthlyBranch {
     pub const EB_CHICKEN: EarthlyBranch = EarthlyBranch::EB10;
     pub const EB_DOG: EarthlyBranch = EarthlyBranch::EB11;
     pub const EB_PIG: EarthlyBranch = EarthlyBranch::EB12;
-}
\ No newline at end of file
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Locale {
+    Vi,
+    ZhHans,
+    ZhHant,
+    Ko,
+    Ja,
+    En,
+}
+
+impl Locale {
+    // BCP 47 tag, with the common region forms (zh-CN, zh-TW, ...) also accepted when parsing
+    pub fn tag(&self) -> &'static str {
+        match self {
+            Locale::Vi => { "vi" }
+            Locale::ZhHans => { "zh-Hans" }
+            Locale::ZhHant => { "zh-Hant" }
+            Locale::Ko => { "ko" }
+            Locale::Ja => { "ja" }
+            Locale::En => { "en" }
+        }
+    }
+
+    pub fn from_tag(tag: &str) -> Option<Locale> {
+        match tag.trim().replace('_', "-").to_lowercase().as_str() {
+            "vi" | "vi-vn" => Some(Locale::Vi),
+            "zh" | "zh-hans" | "zh-cn" | "zh-sg" => Some(Locale::ZhHans),
+            "zh-hant" | "zh-tw" | "zh-hk" | "zh-mo" => Some(Locale::ZhHant),
+            "ko" | "ko-kr" => Some(Locale::Ko),
+            "ja" | "ja-jp" => Some(Locale::Ja),
+            "en" | "en-us" | "en-gb" => Some(Locale::En),
+            _ => None,
+        }
+    }
+
+    fn column(&self) -> usize {
+        match self {
+            Locale::Vi => { 0 }
+            Locale::ZhHans => { 1 }
+            Locale::ZhHant => { 2 }
+            Locale::Ko => { 3 }
+            Locale::Ja => { 4 }
+            Locale::En => { 5 }
+        }
+    }
+}
+
+// Latin transcriptions of the Chinese names
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Romanization {
+    // Hanyu pinyin with tone marks
+    Pinyin,
+    // Sino-Vietnamese reading
+    HanViet,
+}
+
+impl Romanization {
+    fn column(&self) -> usize {
+        match self {
+            Romanization::Pinyin => { 6 }
+            Romanization::HanViet => { 7 }
+        }
+    }
+}
+
+// Columns: vi, zh-Hans, zh-Hant, ko, ja, en, pinyin, Hán-Việt
+type Names = [&'static str; 8];
+
+const STEM_NAMES: [Names; 10] = [
+    ["Giáp", "甲", "甲", "갑", "甲", "Yang Wood", "Jiǎ", "Giáp"],
+    ["Ất", "乙", "乙", "을", "乙", "Yin Wood", "Yǐ", "Ất"],
+    ["Bính", "丙", "丙", "병", "丙", "Yang Fire", "Bǐng", "Bính"],
+    ["Đinh", "丁", "丁", "정", "丁", "Yin Fire", "Dīng", "Đinh"],
+    ["Mậu", "戊", "戊", "무", "戊", "Yang Earth", "Wù", "Mậu"],
+    ["Kỷ", "己", "己", "기", "己", "Yin Earth", "Jǐ", "Kỷ"],
+    ["Canh", "庚", "庚", "경", "庚", "Yang Metal", "Gēng", "Canh"],
+    ["Tân", "辛", "辛", "신", "辛", "Yin Metal", "Xīn", "Tân"],
+    ["Nhâm", "壬", "壬", "임", "壬", "Yang Water", "Rén", "Nhâm"],
+    ["Quý", "癸", "癸", "계", "癸", "Yin Water", "Guǐ", "Quý"],
+];
+
+const BRANCH_NAMES: [Names; 12] = [
+    ["Tý", "子", "子", "자", "子", "Rat", "Zǐ", "Tý"],
+    ["Sửu", "丑", "丑", "축", "丑", "Ox", "Chǒu", "Sửu"],
+    ["Dần", "寅", "寅", "인", "寅", "Tiger", "Yín", "Dần"],
+    ["Mão", "卯", "卯", "묘", "卯", "Rabbit", "Mǎo", "Mão"],
+    ["Thìn", "辰", "辰", "진", "辰", "Dragon", "Chén", "Thìn"],
+    ["Tỵ", "巳", "巳", "사", "巳", "Snake", "Sì", "Tỵ"],
+    ["Ngọ", "午", "午", "오", "午", "Horse", "Wǔ", "Ngọ"],
+    ["Mùi", "未", "未", "미", "未", "Goat", "Wèi", "Mùi"],
+    ["Thân", "申", "申", "신", "申", "Monkey", "Shēn", "Thân"],
+    ["Dậu", "酉", "酉", "유", "酉", "Rooster", "Yǒu", "Dậu"],
+    ["Tuất", "戌", "戌", "술", "戌", "Dog", "Xū", "Tuất"],
+    ["Hợi", "亥", "亥", "해", "亥", "Pig", "Hài", "Hợi"],
+];
+
+// The animals as they are, so Cat is a cat in every language, in Zodiac order
+const ZODIAC_NAMES: [Names; 18] = [
+    ["Chuột", "鼠", "鼠", "쥐", "鼠", "Rat", "Shǔ", "Thử"],
+    ["Trâu", "水牛", "水牛", "물소", "水牛", "Buffalo", "Shuǐniú", "Thủy ngưu"],
+    ["Hổ", "虎", "虎", "호랑이", "虎", "Tiger", "Hǔ", "Hổ"],
+    ["Mèo", "猫", "貓", "고양이", "猫", "Cat", "Māo", "Miêu"],
+    ["Rồng", "龙", "龍", "용", "龍", "Dragon", "Lóng", "Long"],
+    ["Rắn", "蛇", "蛇", "뱀", "蛇", "Snake", "Shé", "Xà"],
+    ["Ngựa", "马", "馬", "말", "馬", "Horse", "Mǎ", "Mã"],
+    ["Dê", "羊", "羊", "염소", "山羊", "Goat", "Yáng", "Dương"],
+    ["Khỉ", "猴", "猴", "원숭이", "猿", "Monkey", "Hóu", "Hầu"],
+    ["Gà", "鸡", "雞", "닭", "鶏", "Chicken", "Jī", "Kê"],
+    ["Chó", "狗", "狗", "개", "犬", "Dog", "Gǒu", "Cẩu"],
+    ["Lợn", "猪", "豬", "돼지", "豚", "Pig", "Zhū", "Trư"],
+    ["Bò", "牛", "牛", "소", "牛", "Ox", "Niú", "Ngưu"],
+    ["Thỏ", "兔", "兔", "토끼", "兎", "Rabbit", "Tù", "Thố"],
+    ["Cừu", "绵羊", "綿羊", "양", "羊", "Sheep", "Miányáng", "Miên dương"],
+    ["Gà trống", "鸡", "雞", "닭", "鶏", "Rooster", "Jī", "Kê"],
+    ["Lợn rừng", "野猪", "野豬", "멧돼지", "猪", "Boar", "Yězhū", "Dã trư"],
+    ["Rắn thần", "那伽", "那伽", "나가", "ナーガ", "Naga", "Nàqié", "Na-già"],
+];
+
+const ELEMENT_NAMES: [Names; 5] = [
+    ["Kim", "金", "金", "금", "金", "Metal", "Jīn", "Kim"],
+    ["Mộc", "木", "木", "목", "木", "Wood", "Mù", "Mộc"],
+    ["Thủy", "水", "水", "수", "水", "Water", "Shuǐ", "Thủy"],
+    ["Hỏa", "火", "火", "화", "火", "Fire", "Huǒ", "Hỏa"],
+    ["Thổ", "土", "土", "토", "土", "Earth", "Tǔ", "Thổ"],
+];
+
+const YINYANG_NAMES: [Names; 2] = [
+    ["Âm", "阴", "陰", "음", "陰", "Yin", "Yīn", "Âm"],
+    ["Dương", "阳", "陽", "양", "陽", "Yang", "Yáng", "Dương"],
+];
+
+// In SolarTerm order, starting from Xuân phân
+const SOLAR_TERM_NAMES: [Names; 24] = [
+    ["Xuân phân", "春分", "春分", "춘분", "春分", "Spring Equinox", "Chūnfēn", "Xuân phân"],
+    ["Thanh minh", "清明", "清明", "청명", "清明", "Clear and Bright", "Qīngmíng", "Thanh minh"],
+    ["Cốc vũ", "谷雨", "穀雨", "곡우", "穀雨", "Grain Rain", "Gǔyǔ", "Cốc vũ"],
+    ["Lập hạ", "立夏", "立夏", "입하", "立夏", "Start of Summer", "Lìxià", "Lập hạ"],
+    ["Tiểu mãn", "小满", "小滿", "소만", "小満", "Grain Buds", "Xiǎomǎn", "Tiểu mãn"],
+    ["Mang chủng", "芒种", "芒種", "망종", "芒種", "Grain in Ear", "Mángzhòng", "Mang chủng"],
+    ["Hạ chí", "夏至", "夏至", "하지", "夏至", "Summer Solstice", "Xiàzhì", "Hạ chí"],
+    ["Tiểu thử", "小暑", "小暑", "소서", "小暑", "Minor Heat", "Xiǎoshǔ", "Tiểu thử"],
+    ["Đại thử", "大暑", "大暑", "대서", "大暑", "Major Heat", "Dàshǔ", "Đại thử"],
+    ["Lập thu", "立秋", "立秋", "입추", "立秋", "Start of Autumn", "Lìqiū", "Lập thu"],
+    ["Xử thử", "处暑", "處暑", "처서", "処暑", "End of Heat", "Chǔshǔ", "Xử thử"],
+    ["Bạch lộ", "白露", "白露", "백로", "白露", "White Dew", "Báilù", "Bạch lộ"],
+    ["Thu phân", "秋分", "秋分", "추분", "秋分", "Autumn Equinox", "Qiūfēn", "Thu phân"],
+    ["Hàn lộ", "寒露", "寒露", "한로", "寒露", "Cold Dew", "Hánlù", "Hàn lộ"],
+    ["Sương giáng", "霜降", "霜降", "상강", "霜降", "Frost's Descent", "Shuāngjiàng", "Sương giáng"],
+    ["Lập đông", "立冬", "立冬", "입동", "立冬", "Start of Winter", "Lìdōng", "Lập đông"],
+    ["Tiểu tuyết", "小雪", "小雪", "소설", "小雪", "Minor Snow", "Xiǎoxuě", "Tiểu tuyết"],
+    ["Đại tuyết", "大雪", "大雪", "대설", "大雪", "Major Snow", "Dàxuě", "Đại tuyết"],
+    ["Đông chí", "冬至", "冬至", "동지", "冬至", "Winter Solstice", "Dōngzhì", "Đông chí"],
+    ["Tiểu hàn", "小寒", "小寒", "소한", "小寒", "Minor Cold", "Xiǎohán", "Tiểu hàn"],
+    ["Đại hàn", "大寒", "大寒", "대한", "大寒", "Major Cold", "Dàhán", "Đại hàn"],
+    ["Lập xuân", "立春", "立春", "입춘", "立春", "Start of Spring", "Lìchūn", "Lập xuân"],
+    ["Vũ thủy", "雨水", "雨水", "우수", "雨水", "Rain Water", "Yǔshuǐ", "Vũ thủy"],
+    ["Kinh trập", "惊蛰", "驚蟄", "경칩", "啓蟄", "Awakening of Insects", "Jīngzhé", "Kinh trập"],
+];
+
+// Traditional month names, Japanese uses the wafū getsumei
+const MONTH_NAMES: [Names; 12] = [
+    ["Tháng Giêng", "正月", "正月", "정월", "睦月", "First Month", "Zhēngyuè", "Chính nguyệt"],
+    ["Tháng Hai", "二月", "二月", "이월", "如月", "Second Month", "Èryuè", "Nhị nguyệt"],
+    ["Tháng Ba", "三月", "三月", "삼월", "弥生", "Third Month", "Sānyuè", "Tam nguyệt"],
+    ["Tháng Tư", "四月", "四月", "사월", "卯月", "Fourth Month", "Sìyuè", "Tứ nguyệt"],
+    ["Tháng Năm", "五月", "五月", "오월", "皐月", "Fifth Month", "Wǔyuè", "Ngũ nguyệt"],
+    ["Tháng Sáu", "六月", "六月", "유월", "水無月", "Sixth Month", "Liùyuè", "Lục nguyệt"],
+    ["Tháng Bảy", "七月", "七月", "칠월", "文月", "Seventh Month", "Qīyuè", "Thất nguyệt"],
+    ["Tháng Tám", "八月", "八月", "팔월", "葉月", "Eighth Month", "Bāyuè", "Bát nguyệt"],
+    ["Tháng Chín", "九月", "九月", "구월", "長月", "Ninth Month", "Jiǔyuè", "Cửu nguyệt"],
+    ["Tháng Mười", "十月", "十月", "시월", "神無月", "Tenth Month", "Shíyuè", "Thập nguyệt"],
+    ["Tháng Mười một", "冬月", "冬月", "동짓달", "霜月", "Eleventh Month", "Dōngyuè", "Đông nguyệt"],
+    ["Tháng Chạp", "腊月", "臘月", "섣달", "師走", "Twelfth Month", "Làyuè", "Lạp nguyệt"],
+];
+
+impl HeavenlyStem {
+    pub fn localized_name(&self, locale: &Locale) -> &'static str {
+        STEM_NAMES[self.to_numeric() as usize][locale.column()]
+    }
+
+    pub fn romanized_name(&self, romanization: &Romanization) -> &'static str {
+        STEM_NAMES[self.to_numeric() as usize][romanization.column()]
+    }
+
+    pub fn vietnamese_name(&self) -> &'static str {
+        self.localized_name(&Locale::Vi)
+    }
+
+    pub fn from_vietnamese_name(name: &str) -> Option<HeavenlyStem> {
+        HeavenlyStem::ALL.into_iter()
+            .find(|stem| stem.vietnamese_name().to_lowercase() == name.trim().to_lowercase())
+    }
+}
+
+impl EarthlyBranch {
+    pub fn localized_name(&self, locale: &Locale) -> &'static str {
+        BRANCH_NAMES[self.to_numeric() as usize][locale.column()]
+    }
+
+    pub fn romanized_name(&self, romanization: &Romanization) -> &'static str {
+        BRANCH_NAMES[self.to_numeric() as usize][romanization.column()]
+    }
+
+    pub fn vietnamese_name(&self) -> &'static str {
+        self.localized_name(&Locale::Vi)
+    }
+
+    pub fn from_vietnamese_name(name: &str) -> Option<EarthlyBranch> {
+        EarthlyBranch::ALL.into_iter()
+            .find(|branch| branch.vietnamese_name().to_lowercase() == name.trim().to_lowercase())
+    }
+}
+
+impl Zodiac {
+    // ZODIAC_NAMES rows follow the variant order
+    fn name_index(&self) -> usize {
+        *self as usize
+    }
+
+    pub fn localized_name(&self, locale: &Locale) -> &'static str {
+        ZODIAC_NAMES[self.name_index()][locale.column()]
+    }
+
+    pub fn romanized_name(&self, romanization: &Romanization) -> &'static str {
+        ZODIAC_NAMES[self.name_index()][romanization.column()]
+    }
+
+    pub fn vietnamese_name(&self) -> &'static str {
+        self.localized_name(&Locale::Vi)
+    }
+
+    pub fn from_vietnamese_name(name: &str) -> Option<Zodiac> {
+        EarthlyBranch::ALL.into_iter().map(Zodiac::from)
+            .find(|zodiac| zodiac.vietnamese_name().to_lowercase() == name.trim().to_lowercase())
+    }
+}
+
+// Any name of the tables, case-insensitively, so "Giáp", "甲", "Jiǎ" and "yang wood" all parse
+fn has_name(names: &Names, name: &str) -> bool {
+    let name = name.trim().to_lowercase();
+    names.iter().any(|candidate| candidate.to_lowercase() == name)
+}
+
+impl FromStr for HeavenlyStem {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<HeavenlyStem> {
+        HeavenlyStem::ALL.into_iter()
+            .find(|stem| has_name(&STEM_NAMES[stem.to_numeric() as usize], name))
+            .ok_or_else(|| anyhow!("unknown heavenly stem {}", name))
+    }
+}
+
+impl FromStr for EarthlyBranch {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<EarthlyBranch> {
+        EarthlyBranch::ALL.into_iter()
+            .find(|branch| has_name(&BRANCH_NAMES[branch.to_numeric() as usize], name))
+            .ok_or_else(|| anyhow!("unknown earthly branch {}", name))
+    }
+}
+
+// Names shared by two animals, such as 羊 for Goat and Sheep, give the Vietnamese one
+impl FromStr for Zodiac {
+    type Err = anyhow::Error;
+
+    fn from_str(name: &str) -> Result<Zodiac> {
+        Zodiac::ALL.into_iter()
+            .find(|zodiac| has_name(&ZODIAC_NAMES[zodiac.name_index()], name))
+            .ok_or_else(|| anyhow!("unknown zodiac {}", name))
+    }
+}
+
+impl Element {
+    fn name_index(&self) -> usize {
+        match self {
+            Element::Metal => { 0 }
+            Element::Wood => { 1 }
+            Element::Water => { 2 }
+            Element::Fire => { 3 }
+            Element::Earth => { 4 }
+        }
+    }
+
+    pub fn localized_name(&self, locale: &Locale) -> &'static str {
+        ELEMENT_NAMES[self.name_index()][locale.column()]
+    }
+
+    pub fn romanized_name(&self, romanization: &Romanization) -> &'static str {
+        ELEMENT_NAMES[self.name_index()][romanization.column()]
+    }
+
+    pub fn vietnamese_name(&self) -> &'static str {
+        self.localized_name(&Locale::Vi)
+    }
+}
+
+impl YinYang {
+    fn name_index(&self) -> usize {
+        match self {
+            YinYang::Yin => { 0 }
+            YinYang::Yang => { 1 }
+        }
+    }
+
+    pub fn localized_name(&self, locale: &Locale) -> &'static str {
+        YINYANG_NAMES[self.name_index()][locale.column()]
+    }
+
+    pub fn romanized_name(&self, romanization: &Romanization) -> &'static str {
+        YINYANG_NAMES[self.name_index()][romanization.column()]
+    }
+
+    pub fn vietnamese_name(&self) -> &'static str {
+        self.localized_name(&Locale::Vi)
+    }
+}
+
+impl SolarTerm {
+    pub fn localized_name(&self, locale: &Locale) -> &'static str {
+        SOLAR_TERM_NAMES[self.to_numeric() as usize][locale.column()]
+    }
+
+    pub fn romanized_name(&self, romanization: &Romanization) -> &'static str {
+        SOLAR_TERM_NAMES[self.to_numeric() as usize][romanization.column()]
+    }
+}
+
+fn month_name(month: u32, leap: bool, column: usize) -> Option<String> {
+    let name = MONTH_NAMES.get((month as usize).checked_sub(1)?)?[column];
+    if !leap {
+        return Some(name.to_string());
+    }
+    Some(match column {
+        0 => format!("{} nhuận", name),
+        1 => format!("闰{}", name),
+        2 | 4 => format!("閏{}", name),
+        3 => format!("윤{}", name),
+        5 => format!("Leap {}", name),
+        6 => format!("Rùn{}", name.to_lowercase()),
+        _ => format!("Nhuận {}", name.to_lowercase()),
+    })
+}
+
+// "Tháng Giêng", "正月", "臘月", "閏卯月" for a month between 1 and 12
+pub fn lunisolar_month_name(month: u32, leap: bool, locale: &Locale) -> Option<String> {
+    month_name(month, leap, locale.column())
+}
+
+// "Zhēngyuè", "Rùnsìyuè", "Lạp nguyệt"
+pub fn lunisolar_month_romanized_name(month: u32, leap: bool, romanization: &Romanization) -> Option<String> {
+    month_name(month, leap, romanization.column())
+}
+
+impl NaiveLunisolarDateTime {
+    pub fn localized_month_name(&self, locale: &Locale) -> String {
+        month_name(self.month_lunisolar, self.leap_lunisolar, locale.column()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use super::*;
+
+    #[rstest]
+    #[case(Locale::Vi, "Giáp", "Thìn", "Rồng", "Mộc", "Dương")]
+    #[case(Locale::ZhHans, "甲", "辰", "龙", "木", "阳")]
+    #[case(Locale::ZhHant, "甲", "辰", "龍", "木", "陽")]
+    #[case(Locale::Ko, "갑", "진", "용", "목", "양")]
+    #[case(Locale::Ja, "甲", "辰", "龍", "木", "陽")]
+    #[case(Locale::En, "Yang Wood", "Dragon", "Dragon", "Wood", "Yang")]
+    fn test_localized_name(#[case] locale: Locale, #[case] stem: &str, #[case] branch: &str,
+                           #[case] zodiac: &str, #[case] element: &str, #[case] yinyang: &str) {
+        let (element_of_stem, yinyang_of_stem) = HeavenlyStem::HS_GIAP.get_yinyang_elem();
+        assert_eq!(HeavenlyStem::HS_GIAP.localized_name(&locale), stem);
+        assert_eq!(EarthlyBranch::EB_DRAGON.localized_name(&locale), branch);
+        assert_eq!(Zodiac::Dragon.localized_name(&locale), zodiac);
+        assert_eq!(element_of_stem.localized_name(&locale), element);
+        assert_eq!(yinyang_of_stem.localized_name(&locale), yinyang);
+    }
+
+    #[test]
+    fn test_romanized_name() {
+        assert_eq!(HeavenlyStem::HS_GIAP.romanized_name(&Romanization::Pinyin), "Jiǎ");
+        assert_eq!(EarthlyBranch::EB_RAT.romanized_name(&Romanization::Pinyin), "Zǐ");
+        assert_eq!(Zodiac::Dragon.romanized_name(&Romanization::HanViet), "Long");
+        assert_eq!(SolarTerm::LapXuan.romanized_name(&Romanization::Pinyin), "Lìchūn");
+        assert_eq!(SolarTerm::KinhTrap.localized_name(&Locale::Ja), "啓蟄");
+        assert_eq!(SolarTerm::DongChi.localized_name(&Locale::Vi), "Đông chí");
+    }
+
+    #[rstest]
+    #[case(ZodiacTradition::Vietnamese, Locale::Vi, "Mèo", "Trâu", "Gà")]
+    #[case(ZodiacTradition::Chinese, Locale::ZhHans, "兔", "牛", "鸡")]
+    #[case(ZodiacTradition::Chinese, Locale::En, "Rabbit", "Ox", "Rooster")]
+    #[case(ZodiacTradition::Korean, Locale::Ko, "토끼", "소", "닭")]
+    #[case(ZodiacTradition::Japanese, Locale::Ja, "兎", "牛", "鶏")]
+    fn test_zodiac_tradition(#[case] tradition: ZodiacTradition, #[case] locale: Locale,
+                             #[case] rabbit: &str, #[case] ox: &str, #[case] rooster: &str) {
+        assert_eq!(EarthlyBranch::EB_CAT.as_zodiac_in(&tradition).localized_name(&locale), rabbit);
+        assert_eq!(EarthlyBranch::EB_BUFFALO.as_zodiac_in(&tradition).localized_name(&locale), ox);
+        assert_eq!(EarthlyBranch::EB_CHICKEN.as_zodiac_in(&tradition).localized_name(&locale), rooster);
+    }
+
+    #[test]
+    fn test_month_name() {
+        assert_eq!(lunisolar_month_name(1, false, &Locale::Vi).unwrap(), "Tháng Giêng");
+        assert_eq!(lunisolar_month_name(12, false, &Locale::Vi).unwrap(), "Tháng Chạp");
+        assert_eq!(lunisolar_month_name(1, false, &Locale::ZhHans).unwrap(), "正月");
+        assert_eq!(lunisolar_month_name(12, false, &Locale::ZhHant).unwrap(), "臘月");
+        assert_eq!(lunisolar_month_name(4, true, &Locale::ZhHans).unwrap(), "闰四月");
+        assert_eq!(lunisolar_month_name(4, true, &Locale::Vi).unwrap(), "Tháng Tư nhuận");
+        assert_eq!(lunisolar_month_name(4, true, &Locale::En).unwrap(), "Leap Fourth Month");
+        assert_eq!(lunisolar_month_romanized_name(4, true, &Romanization::Pinyin).unwrap(), "Rùnsìyuè");
+        assert_eq!(lunisolar_month_name(13, false, &Locale::Ko), None);
+        assert_eq!(lunisolar_month_name(0, false, &Locale::Ko), None);
+    }
+
+    #[test]
+    fn test_locale_tag() {
+        assert_eq!(Locale::from_tag("zh_TW"), Some(Locale::ZhHant));
+        assert_eq!(Locale::from_tag("zh-Hans"), Some(Locale::ZhHans));
+        assert_eq!(Locale::from_tag("fr"), None);
+        assert_eq!(Locale::Ko.tag(), "ko");
+    }
+
+    #[test]
+    fn test_from_str() {
+        assert_eq!("Giáp".parse::<HeavenlyStem>().unwrap(), HeavenlyStem::HS_GIAP);
+        assert_eq!("癸".parse::<HeavenlyStem>().unwrap(), HeavenlyStem::HS_QUY);
+        assert_eq!(" yang wood ".parse::<HeavenlyStem>().unwrap(), HeavenlyStem::HS_GIAP);
+        assert_eq!("tỵ".parse::<EarthlyBranch>().unwrap(), EarthlyBranch::EB_SNAKE);
+        assert_eq!("Tý".parse::<EarthlyBranch>().unwrap(), EarthlyBranch::EB_RAT);
+        assert_eq!("Zǐ".parse::<EarthlyBranch>().unwrap(), EarthlyBranch::EB_RAT);
+        assert_eq!("Mèo".parse::<Zodiac>().unwrap(), Zodiac::Cat);
+        assert_eq!("Rabbit".parse::<Zodiac>().unwrap(), Zodiac::Rabbit);
+        assert!("Foo".parse::<HeavenlyStem>().is_err());
+        assert!("".parse::<EarthlyBranch>().is_err());
+    }
+}
@@ -1,11 +1,11 @@
 use chrono::{NaiveDate};
-use anyhow::Result;
+use anyhow::{bail, Result};
 use chrono::{Datelike, DateTime, FixedOffset, Timelike};
 use super::lunisolar_datetime::*;
 use super::zodiac_and_element::*;
-use std::cmp::PartialEq;
+use std::cmp::{Ordering, PartialEq};
 
-#[derive(Clone, Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum HeavenlyStem {
     HS1,
     HS2,
@@ -20,35 +20,27 @@ pub enum HeavenlyStem {
 }
 
 impl HeavenlyStem {
+    // Giáp to Quý, in cycle order
+    pub const ALL: [HeavenlyStem; 10] = [
+        HeavenlyStem::HS1, HeavenlyStem::HS2, HeavenlyStem::HS3, HeavenlyStem::HS4, HeavenlyStem::HS5,
+        HeavenlyStem::HS6, HeavenlyStem::HS7, HeavenlyStem::HS8, HeavenlyStem::HS9, HeavenlyStem::HS10,
+    ];
+
     pub fn from_numeric(num: u32) -> HeavenlyStem {
-        match num % 10 {
-            0 => HeavenlyStem::HS1,
-            1 => HeavenlyStem::HS2,
-            2 => HeavenlyStem::HS3,
-            3 => HeavenlyStem::HS4,
-            4 => HeavenlyStem::HS5,
-            5 => HeavenlyStem::HS6,
-            6 => HeavenlyStem::HS7,
-            7 => HeavenlyStem::HS8,
-            8 => HeavenlyStem::HS9,
-            9 => HeavenlyStem::HS10,
-            _ => panic!("invalid numeric for heavenly stem")
-        }
+        HeavenlyStem::ALL[(num % 10) as usize]
     }
 
     pub fn to_numeric(&self) -> u32 {
-        match self {
-            HeavenlyStem::HS1 => 0,
-            HeavenlyStem::HS2 => 1,
-            HeavenlyStem::HS3 => 2,
-            HeavenlyStem::HS4 => 3,
-            HeavenlyStem::HS5 => 4,
-            HeavenlyStem::HS6 => 5,
-            HeavenlyStem::HS7 => 6,
-            HeavenlyStem::HS8 => 7,
-            HeavenlyStem::HS9 => 8,
-            HeavenlyStem::HS10 => 9,
-        }
+        *self as u32
+    }
+
+    // Next stem, Quý wrapping back to Giáp
+    pub fn succ(&self) -> HeavenlyStem {
+        HeavenlyStem::from_numeric(self.to_numeric() + 1)
+    }
+
+    pub fn pred(&self) -> HeavenlyStem {
+        HeavenlyStem::from_numeric(self.to_numeric() + 9)
     }
 
     pub fn get_yinyang_elem(&self) -> (Element, YinYang){
@@ -91,7 +83,7 @@ impl HeavenlyStem {
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
 pub enum EarthlyBranch {
     EB1,
     EB2,
@@ -108,39 +100,28 @@ pub enum EarthlyBranch {
 }
 
 impl EarthlyBranch {
+    // Tý to Hợi, in cycle order
+    pub const ALL: [EarthlyBranch; 12] = [
+        EarthlyBranch::EB1, EarthlyBranch::EB2, EarthlyBranch::EB3, EarthlyBranch::EB4,
+        EarthlyBranch::EB5, EarthlyBranch::EB6, EarthlyBranch::EB7, EarthlyBranch::EB8,
+        EarthlyBranch::EB9, EarthlyBranch::EB10, EarthlyBranch::EB11, EarthlyBranch::EB12,
+    ];
+
     pub fn from_numeric(num: u32) -> EarthlyBranch {
-        match num % 12 {
-            0 => EarthlyBranch::EB1,
-            1 => EarthlyBranch::EB2,
-            2 => EarthlyBranch::EB3,
-            3 => EarthlyBranch::EB4,
-            4 => EarthlyBranch::EB5,
-            5 => EarthlyBranch::EB6,
-            6 => EarthlyBranch::EB7,
-            7 => EarthlyBranch::EB8,
-            8 => EarthlyBranch::EB9,
-            9 => EarthlyBranch::EB10,
-            10 => EarthlyBranch::EB11,
-            11 => EarthlyBranch::EB12,
-            _ => panic!("invalid numeric for heavenly stem")
-        }
+        EarthlyBranch::ALL[(num % 12) as usize]
     }
 
     pub fn to_numeric(&self) -> u32 {
-        match self {
-            EarthlyBranch::EB1 => 0,
-            EarthlyBranch::EB2 => 1,
-            EarthlyBranch::EB3 => 2,
-            EarthlyBranch::EB4 => 3,
-            EarthlyBranch::EB5 => 4,
-            EarthlyBranch::EB6 => 5,
-            EarthlyBranch::EB7 => 6,
-            EarthlyBranch::EB8 => 7,
-            EarthlyBranch::EB9 => 8,
-            EarthlyBranch::EB10 => 9,
-            EarthlyBranch::EB11 => 10,
-            EarthlyBranch::EB12 => 11,
-        }
+        *self as u32
+    }
+
+    // Next branch, Hợi wrapping back to Tý
+    pub fn succ(&self) -> EarthlyBranch {
+        EarthlyBranch::from_numeric(self.to_numeric() + 1)
+    }
+
+    pub fn pred(&self) -> EarthlyBranch {
+        EarthlyBranch::from_numeric(self.to_numeric() + 11)
     }
 
     pub fn get_yinyang_elem(&self) -> (Element, YinYang){
@@ -159,20 +140,20 @@ impl EarthlyBranch {
             EarthlyBranch::EB12 => {(Element::Water, YinYang::Yin)}
         }
     }
-    pub fn as_zodiac(&self) -> Zodiac{
-        match self{
-            EarthlyBranch::EB1 => {Zodiac::Rat}
-            EarthlyBranch::EB2 => {Zodiac::Buffalo}
-            EarthlyBranch::EB3 => {Zodiac::Tiger}
-            EarthlyBranch::EB4 => {Zodiac::Cat}
-            EarthlyBranch::EB5 => {Zodiac::Dragon}
-            EarthlyBranch::EB6 => {Zodiac::Snake}
-            EarthlyBranch::EB7 => {Zodiac::Horse}
-            EarthlyBranch::EB8 => {Zodiac::Goat}
-            EarthlyBranch::EB9 => {Zodiac::Monkey}
-            EarthlyBranch::EB10 => {Zodiac::Chicken}
-            EarthlyBranch::EB11 => {Zodiac::Dog}
-            EarthlyBranch::EB12 => {Zodiac::Pig}
+    pub fn as_zodiac(&self) -> Zodiac {
+        Zodiac::from(*self)
+    }
+
+    pub fn as_zodiac_in(&self, tradition: &ZodiacTradition) -> Zodiac {
+        match (self, tradition) {
+            (_, ZodiacTradition::Vietnamese) => { self.as_zodiac() }
+            (EarthlyBranch::EB2, _) => { Zodiac::Ox }
+            (EarthlyBranch::EB4, _) => { Zodiac::Rabbit }
+            (EarthlyBranch::EB5, ZodiacTradition::Thai) => { Zodiac::Naga }
+            (EarthlyBranch::EB8, ZodiacTradition::Japanese | ZodiacTradition::Korean) => { Zodiac::Sheep }
+            (EarthlyBranch::EB10, _) => { Zodiac::Rooster }
+            (EarthlyBranch::EB12, ZodiacTradition::Japanese) => { Zodiac::Boar }
+            _ => { self.as_zodiac() }
         }
     }
     pub fn lunar_y_from_lunisolar_y(year: u32) -> EarthlyBranch {
@@ -191,11 +172,36 @@ impl EarthlyBranch {
     }
 
     pub fn lunar_h_from_gregorian_h(hour: u32) -> EarthlyBranch {
-        EarthlyBranch::from_numeric( ((hour+1)/2 )%12)
+        EarthlyBranch::from_numeric( hour.div_ceil(2) %12)
     }
 }
 
-#[derive(Clone, Debug, PartialEq)]
+// 0 for Giáp to 9 for Quý, the same numbering as from_numeric but without wrapping
+impl TryFrom<u8> for HeavenlyStem {
+    type Error = anyhow::Error;
+
+    fn try_from(num: u8) -> Result<HeavenlyStem> {
+        match HeavenlyStem::ALL.get(num as usize) {
+            Some(stem) => Ok(*stem),
+            None => bail!("invalid numeric for heavenly stem: {}", num),
+        }
+    }
+}
+
+// 0 for Tý to 11 for Hợi
+impl TryFrom<u8> for EarthlyBranch {
+    type Error = anyhow::Error;
+
+    fn try_from(num: u8) -> Result<EarthlyBranch> {
+        match EarthlyBranch::ALL.get(num as usize) {
+            Some(branch) => Ok(*branch),
+            None => bail!("invalid numeric for earthly branch: {}", num),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct LunarFormat {
     pub stem: HeavenlyStem,
     pub branch: EarthlyBranch,
@@ -209,6 +215,20 @@ impl LunarFormat {
     }
 }
 
+// Sexagenary order, Giáp Tý first and Quý Hợi last
+impl Ord for LunarFormat {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.sexagenary_index(), self.stem, self.branch)
+            .cmp(&(other.sexagenary_index(), other.stem, other.branch))
+    }
+}
+
+impl PartialOrd for LunarFormat {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 // impl PartialEq for LunarFormat{
 //     fn eq(&self, other: &Self) -> bool {
 //         self.stem == other.stem && self.branch == other.branch
@@ -218,32 +238,52 @@ impl LunarFormat {
 //         self.stem != other.stem || self.branch != other.branch
 //     }
 // }
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct NaiveLunarDateTime {
+    #[cfg_attr(feature = "serde", serde(rename = "hour"))]
     pub hour_lunar: LunarFormat,
+    #[cfg_attr(feature = "serde", serde(rename = "day"))]
     pub date_lunar: LunarFormat,
+    #[cfg_attr(feature = "serde", serde(rename = "month"))]
     pub month_lunar: LunarFormat,
+    #[cfg_attr(feature = "serde", serde(rename = "year"))]
     pub year_lunar: LunarFormat,
 }
 
+// Year pillar first, then month, day and hour, each in sexagenary order. Every pillar restarts at
+// Giáp Tý after sixty steps, so this orders the pillars, not the instants they were taken from.
+impl Ord for NaiveLunarDateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.year_lunar, self.month_lunar, self.date_lunar, self.hour_lunar)
+            .cmp(&(other.year_lunar, other.month_lunar, other.date_lunar, other.hour_lunar))
+    }
+}
+
+impl PartialOrd for NaiveLunarDateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl NaiveLunarDateTime {
     pub fn new_from_datetime(datetime: DateTime<FixedOffset>) -> Result<NaiveLunarDateTime>{
-        let naive_lunisolar = NaiveLunisolarDateTime::new_from_datetime(datetime.clone())?;
+        let naive_lunisolar = NaiveLunisolarDateTime::new_from_datetime(datetime)?;
         let hour = datetime.hour();
         let day = datetime.day();
         let month = datetime.month();
         let year =  datetime.year() as u32;
-        let hour_hs =  HeavenlyStem::lunar_h_from_gregorian_hymd(hour.clone(), day.clone(), month.clone(), year.clone());
-        let hour_eb =  EarthlyBranch::lunar_h_from_gregorian_h(hour.clone());
+        let hour_hs =  HeavenlyStem::lunar_h_from_gregorian_hymd(hour, day, month, year);
+        let hour_eb =  EarthlyBranch::lunar_h_from_gregorian_h(hour);
 
-        let day_hs =  HeavenlyStem::lunar_d_from_gregorian_ymd(day.clone(), month.clone(), year.clone());
-        let day_eb =  EarthlyBranch::lunar_d_from_gregorian_ymd(day.clone(), month.clone(), year.clone());
+        let day_hs =  HeavenlyStem::lunar_d_from_gregorian_ymd(day, month, year);
+        let day_eb =  EarthlyBranch::lunar_d_from_gregorian_ymd(day, month, year);
 
-        let month_hs =  HeavenlyStem::lunar_m_from_lunisolar_ym(naive_lunisolar.month_lunisolar.clone(), naive_lunisolar.year_lunisolar.clone());
-        let month_eb =  EarthlyBranch::lunar_m_from_lunisolar_m(naive_lunisolar.month_lunisolar.clone());
+        let month_hs =  HeavenlyStem::lunar_m_from_lunisolar_ym(naive_lunisolar.month_lunisolar, naive_lunisolar.year_lunisolar);
+        let month_eb =  EarthlyBranch::lunar_m_from_lunisolar_m(naive_lunisolar.month_lunisolar);
 
-        let year_hs =  HeavenlyStem::lunar_y_from_lunisolar_y( naive_lunisolar.year_lunisolar.clone());
-        let year_eb =  EarthlyBranch::lunar_y_from_lunisolar_y(naive_lunisolar.year_lunisolar.clone());
+        let year_hs =  HeavenlyStem::lunar_y_from_lunisolar_y( naive_lunisolar.year_lunisolar);
+        let year_eb =  EarthlyBranch::lunar_y_from_lunisolar_y(naive_lunisolar.year_lunisolar);
 
         Ok(NaiveLunarDateTime{
             hour_lunar: LunarFormat { stem: hour_hs, branch: hour_eb },
@@ -286,4 +326,61 @@ mod tests {
         assert_eq!(HeavenlyStem::lunar_h_from_gregorian_hymd(10, 9, 11, 1999), HeavenlyStem::HS8);
         assert_eq!(EarthlyBranch::lunar_h_from_gregorian_h(10), EarthlyBranch::EB6);
     }
+
+    #[test]
+    fn test_as_zodiac_in() {
+        assert_eq!(EarthlyBranch::EB4.as_zodiac_in(&ZodiacTradition::Vietnamese), Zodiac::Cat);
+        assert_eq!(EarthlyBranch::EB4.as_zodiac_in(&ZodiacTradition::Chinese), Zodiac::Rabbit);
+        assert_eq!(EarthlyBranch::EB5.as_zodiac_in(&ZodiacTradition::Thai), Zodiac::Naga);
+        assert_eq!(EarthlyBranch::EB8.as_zodiac_in(&ZodiacTradition::Chinese), Zodiac::Goat);
+        assert_eq!(EarthlyBranch::EB8.as_zodiac_in(&ZodiacTradition::Korean), Zodiac::Sheep);
+        assert_eq!(EarthlyBranch::EB12.as_zodiac_in(&ZodiacTradition::Japanese), Zodiac::Boar);
+        assert_eq!(EarthlyBranch::EB12.as_zodiac_in(&ZodiacTradition::Korean), Zodiac::Pig);
+        for tradition in [ZodiacTradition::Vietnamese, ZodiacTradition::Chinese, ZodiacTradition::Japanese,
+                          ZodiacTradition::Korean, ZodiacTradition::Thai] {
+            for branch in EarthlyBranch::ALL {
+                assert_eq!(EarthlyBranch::from(branch.as_zodiac_in(&tradition)), branch);
+            }
+        }
+    }
+
+    #[test]
+    fn test_iteration_and_conversion() {
+        for (num, stem) in HeavenlyStem::ALL.into_iter().enumerate() {
+            assert_eq!(stem.to_numeric(), num as u32);
+            assert_eq!(HeavenlyStem::try_from(num as u8).unwrap(), stem);
+            assert_eq!(stem.succ().pred(), stem);
+        }
+        for (num, branch) in EarthlyBranch::ALL.into_iter().enumerate() {
+            assert_eq!(branch.to_numeric(), num as u32);
+            assert_eq!(EarthlyBranch::try_from(num as u8).unwrap(), branch);
+            assert_eq!(EarthlyBranch::from(Zodiac::from(branch)), branch);
+        }
+        assert!(HeavenlyStem::try_from(10).is_err());
+        assert!(EarthlyBranch::try_from(12).is_err());
+        assert_eq!(HeavenlyStem::HS10.succ(), HeavenlyStem::HS1);
+        assert_eq!(EarthlyBranch::EB1.pred(), EarthlyBranch::EB12);
+        assert_eq!(Zodiac::from(EarthlyBranch::EB4), Zodiac::Cat);
+        assert_eq!(EarthlyBranch::from(Zodiac::Rooster), EarthlyBranch::EB10);
+    }
+
+    #[test]
+    fn test_sexagenary_order() {
+        use chrono::TimeZone;
+        let tz = FixedOffset::east_opt(7 * 3600).unwrap();
+        let pillars: Vec<LunarFormat> = [(10, 15), (2, 15), (6, 15), (12, 15), (4, 15)].into_iter()
+            .map(|(month, day)| {
+                let datetime = tz.with_ymd_and_hms(2024, month, day, 12, 0, 0).unwrap();
+                NaiveLunarDateTime::new_from_datetime(datetime).unwrap().month_lunar
+            })
+            .collect();
+        let mut sorted = pillars.clone();
+        sorted.sort();
+        let expected: Vec<LunarFormat> = [(3, 3), (5, 5), (7, 7), (1, 11), (3, 1)].into_iter()
+            .map(|(stem, branch)| LunarFormat::new(HeavenlyStem::from_numeric(stem - 1), EarthlyBranch::from_numeric(branch - 1)))
+            .collect();
+        assert_eq!(sorted, expected);
+        assert!(LunarFormat::new(HeavenlyStem::HS10, EarthlyBranch::EB12) > LunarFormat::new(HeavenlyStem::HS1, EarthlyBranch::EB11));
+        assert!(LunarFormat::new(HeavenlyStem::HS1, EarthlyBranch::EB1) < LunarFormat::new(HeavenlyStem::HS10, EarthlyBranch::EB2));
+    }
 }
@@ -0,0 +1,248 @@
+use super::zodiac_and_element::*;
+use super::LunarDateTimeObject;
+use chrono::{Datelike, NaiveDate};
+
+// Thất diệu, the seven luminaries ruling the mansions in turn
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Luminary {
+    Wood,
+    Metal,
+    Earth,
+    Sun,
+    Moon,
+    Fire,
+    Water,
+}
+
+impl Luminary {
+    // Sun and Moon do not belong to the five elements
+    pub fn as_element(&self) -> Option<Element> {
+        match self {
+            Luminary::Wood => { Some(Element::Wood) }
+            Luminary::Metal => { Some(Element::Metal) }
+            Luminary::Earth => { Some(Element::Earth) }
+            Luminary::Fire => { Some(Element::Fire) }
+            Luminary::Water => { Some(Element::Water) }
+            Luminary::Sun | Luminary::Moon => { None }
+        }
+    }
+}
+
+// Full Hán-Việt name, animal and traditional rating of each mansion, starting from Giác
+const MANSIONS: [(&str, &str, Fortune); 28] = [
+    ("Giác Mộc Giao", "Flood dragon", Fortune::Good),
+    ("Cang Kim Long", "Dragon", Fortune::Bad),
+    ("Đê Thổ Lạc", "Badger", Fortune::Bad),
+    ("Phòng Nhật Thố", "Rabbit", Fortune::Good),
+    ("Tâm Nguyệt Hồ", "Fox", Fortune::Bad),
+    ("Vĩ Hỏa Hổ", "Tiger", Fortune::Good),
+    ("Cơ Thủy Báo", "Leopard", Fortune::Good),
+    ("Đẩu Mộc Giải", "Unicorn", Fortune::Good),
+    ("Ngưu Kim Ngưu", "Ox", Fortune::Bad),
+    ("Nữ Thổ Bức", "Bat", Fortune::Bad),
+    ("Hư Nhật Thử", "Rat", Fortune::Bad),
+    ("Nguy Nguyệt Yến", "Swallow", Fortune::Bad),
+    ("Thất Hỏa Trư", "Pig", Fortune::Good),
+    ("Bích Thủy Du", "Porcupine", Fortune::Good),
+    ("Khuê Mộc Lang", "Wolf", Fortune::Bad),
+    ("Lâu Kim Cẩu", "Dog", Fortune::Good),
+    ("Vị Thổ Trĩ", "Pheasant", Fortune::Good),
+    ("Mão Nhật Kê", "Rooster", Fortune::Bad),
+    ("Tất Nguyệt Ô", "Crow", Fortune::Good),
+    ("Chủy Hỏa Hầu", "Monkey", Fortune::Bad),
+    ("Sâm Thủy Viên", "Ape", Fortune::Good),
+    ("Tỉnh Mộc Hãn", "Tapir", Fortune::Good),
+    ("Quỷ Kim Dương", "Goat", Fortune::Bad),
+    ("Liễu Thổ Chương", "Muntjac", Fortune::Bad),
+    ("Tinh Nhật Mã", "Horse", Fortune::Bad),
+    ("Trương Nguyệt Lộc", "Deer", Fortune::Good),
+    ("Dực Hỏa Xà", "Snake", Fortune::Bad),
+    ("Chẩn Thủy Dẫn", "Earthworm", Fortune::Good),
+];
+
+// Nhị thập bát tú
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LunarMansion {
+    GiacMocGiao,
+    CangKimLong,
+    DeThoLac,
+    PhongNhatTho,
+    TamNguyetHo,
+    ViHoaHo,
+    CoThuyBao,
+    DauMocGiai,
+    NguuKimNguu,
+    NuThoBuc,
+    HuNhatThu,
+    NguyNguyetYen,
+    ThatHoaTru,
+    BichThuyDu,
+    KhueMocLang,
+    LauKimCau,
+    ViThoTri,
+    MaoNhatKe,
+    TatNguyetO,
+    ChuyHoaHau,
+    SamThuyVien,
+    TinhMocHan,
+    QuyKimDuong,
+    LieuThoChuong,
+    TinhNhatMa,
+    TruongNguyetLoc,
+    DucHoaXa,
+    ChanThuyDan,
+}
+
+impl LunarMansion {
+    pub fn from_numeric(num: u32) -> LunarMansion {
+        match num % 28 {
+            0 => LunarMansion::GiacMocGiao,
+            1 => LunarMansion::CangKimLong,
+            2 => LunarMansion::DeThoLac,
+            3 => LunarMansion::PhongNhatTho,
+            4 => LunarMansion::TamNguyetHo,
+            5 => LunarMansion::ViHoaHo,
+            6 => LunarMansion::CoThuyBao,
+            7 => LunarMansion::DauMocGiai,
+            8 => LunarMansion::NguuKimNguu,
+            9 => LunarMansion::NuThoBuc,
+            10 => LunarMansion::HuNhatThu,
+            11 => LunarMansion::NguyNguyetYen,
+            12 => LunarMansion::ThatHoaTru,
+            13 => LunarMansion::BichThuyDu,
+            14 => LunarMansion::KhueMocLang,
+            15 => LunarMansion::LauKimCau,
+            16 => LunarMansion::ViThoTri,
+            17 => LunarMansion::MaoNhatKe,
+            18 => LunarMansion::TatNguyetO,
+            19 => LunarMansion::ChuyHoaHau,
+            20 => LunarMansion::SamThuyVien,
+            21 => LunarMansion::TinhMocHan,
+            22 => LunarMansion::QuyKimDuong,
+            23 => LunarMansion::LieuThoChuong,
+            24 => LunarMansion::TinhNhatMa,
+            25 => LunarMansion::TruongNguyetLoc,
+            26 => LunarMansion::DucHoaXa,
+            _ => LunarMansion::ChanThuyDan,
+        }
+    }
+
+    pub fn to_numeric(&self) -> u32 {
+        match self {
+            LunarMansion::GiacMocGiao => 0,
+            LunarMansion::CangKimLong => 1,
+            LunarMansion::DeThoLac => 2,
+            LunarMansion::PhongNhatTho => 3,
+            LunarMansion::TamNguyetHo => 4,
+            LunarMansion::ViHoaHo => 5,
+            LunarMansion::CoThuyBao => 6,
+            LunarMansion::DauMocGiai => 7,
+            LunarMansion::NguuKimNguu => 8,
+            LunarMansion::NuThoBuc => 9,
+            LunarMansion::HuNhatThu => 10,
+            LunarMansion::NguyNguyetYen => 11,
+            LunarMansion::ThatHoaTru => 12,
+            LunarMansion::BichThuyDu => 13,
+            LunarMansion::KhueMocLang => 14,
+            LunarMansion::LauKimCau => 15,
+            LunarMansion::ViThoTri => 16,
+            LunarMansion::MaoNhatKe => 17,
+            LunarMansion::TatNguyetO => 18,
+            LunarMansion::ChuyHoaHau => 19,
+            LunarMansion::SamThuyVien => 20,
+            LunarMansion::TinhMocHan => 21,
+            LunarMansion::QuyKimDuong => 22,
+            LunarMansion::LieuThoChuong => 23,
+            LunarMansion::TinhNhatMa => 24,
+            LunarMansion::TruongNguyetLoc => 25,
+            LunarMansion::DucHoaXa => 26,
+            LunarMansion::ChanThuyDan => 27,
+        }
+    }
+
+    // Counted from 1900-01-01, a Tâm day; Giác always falls on a Thursday
+    pub fn from_gregorian_ymd(day: u32, month: u32, year: u32) -> LunarMansion {
+        let first_date = NaiveDate::from_ymd_opt(1900, 1, 1).unwrap();
+        let interest_date = NaiveDate::from_ymd_opt(year as i32, month, day).unwrap();
+        let duration = interest_date.signed_duration_since(first_date).num_days();
+        LunarMansion::from_numeric((duration + 4).rem_euclid(28) as u32)
+    }
+
+    pub fn luminary(&self) -> Luminary {
+        match self.to_numeric() % 7 {
+            0 => Luminary::Wood,
+            1 => Luminary::Metal,
+            2 => Luminary::Earth,
+            3 => Luminary::Sun,
+            4 => Luminary::Moon,
+            5 => Luminary::Fire,
+            _ => Luminary::Water,
+        }
+    }
+
+    pub fn element(&self) -> Option<Element> {
+        self.luminary().as_element()
+    }
+
+    pub fn full_name(&self) -> &'static str {
+        MANSIONS[self.to_numeric() as usize].0
+    }
+
+    pub fn animal(&self) -> &'static str {
+        MANSIONS[self.to_numeric() as usize].1
+    }
+
+    pub fn fortune(&self) -> Fortune {
+        MANSIONS[self.to_numeric() as usize].2
+    }
+}
+
+impl LunarDateTimeObject {
+    pub fn lunar_mansion(&self) -> LunarMansion {
+        let date = self.gregorian_datetime;
+        LunarMansion::from_gregorian_ymd(date.day(), date.month(), date.year() as u32)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use chrono::Weekday;
+    use super::*;
+
+    #[rstest]
+    #[case(1900, 1, 1, LunarMansion::TamNguyetHo)]
+    #[case(1900, 1, 8, LunarMansion::NguyNguyetYen)]
+    #[case(1900, 3, 12, LunarMansion::TatNguyetO)]
+    fn test_from_gregorian_ymd(#[case] yyyy: u32, #[case] mm: u32, #[case] dd: u32, #[case] expected: LunarMansion) {
+        assert_eq!(LunarMansion::from_gregorian_ymd(dd, mm, yyyy), expected);
+    }
+
+    #[test]
+    fn test_luminary_follows_weekday() {
+        let mut date = NaiveDate::from_ymd_opt(2023, 1, 1).unwrap();
+        for _ in 0..100 {
+            let mansion = LunarMansion::from_gregorian_ymd(date.day(), date.month(), date.year() as u32);
+            let expected = match date.weekday() {
+                Weekday::Thu => Luminary::Wood,
+                Weekday::Fri => Luminary::Metal,
+                Weekday::Sat => Luminary::Earth,
+                Weekday::Sun => Luminary::Sun,
+                Weekday::Mon => Luminary::Moon,
+                Weekday::Tue => Luminary::Fire,
+                Weekday::Wed => Luminary::Water,
+            };
+            assert_eq!(mansion.luminary(), expected);
+            date = date.succ_opt().unwrap();
+        }
+    }
+
+    #[test]
+    fn test_attributes() {
+        assert_eq!(LunarMansion::GiacMocGiao.full_name(), "Giác Mộc Giao");
+        assert_eq!(LunarMansion::GiacMocGiao.element(), Some(Element::Wood));
+        assert_eq!(LunarMansion::PhongNhatTho.element(), None);
+        assert_eq!(LunarMansion::KhueMocLang.animal(), "Wolf");
+        assert_eq!(LunarMansion::KhueMocLang.fortune(), Fortune::Bad);
+    }
+}
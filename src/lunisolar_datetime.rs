@@ -1,50 +1,75 @@
-use anyhow::{Result, bail};
-use chrono::{Datelike, DateTime, FixedOffset, Timelike};
+use std::cmp::Ordering;
+use anyhow::{Result, bail, anyhow};
+use chrono::{Datelike, DateTime, FixedOffset, NaiveDate, Timelike};
 
-const MIN_YEAR: i32 = 1900;
-const MAX_YEAR: i32 = 2100;
+pub(crate) const MIN_YEAR: i32 = 1900;
+pub(crate) const MAX_YEAR: i32 = 2100;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize))]
 pub struct NaiveLunisolarDateTime {
+    #[cfg_attr(feature = "serde", serde(rename = "hour"))]
     pub hour_lunisolar: u32,
+    #[cfg_attr(feature = "serde", serde(rename = "minute"))]
     pub minute_lunisolar: u32,
+    #[cfg_attr(feature = "serde", serde(rename = "day"))]
     pub day_lunisolar: u32,
+    #[cfg_attr(feature = "serde", serde(rename = "month"))]
     pub month_lunisolar: u32,
+    // The month repeats the previous one (tháng nhuận)
+    #[cfg_attr(feature = "serde", serde(rename = "leap"))]
+    pub leap_lunisolar: bool,
+    #[cfg_attr(feature = "serde", serde(rename = "year"))]
     pub year_lunisolar: u32,
 }
+// Chronological, a leap month coming right after the regular month of the same number
+impl Ord for NaiveLunisolarDateTime {
+    fn cmp(&self, other: &Self) -> Ordering {
+        (self.year_lunisolar, self.month_lunisolar, self.leap_lunisolar, self.day_lunisolar, self.hour_lunisolar, self.minute_lunisolar)
+            .cmp(&(other.year_lunisolar, other.month_lunisolar, other.leap_lunisolar, other.day_lunisolar, other.hour_lunisolar, other.minute_lunisolar))
+    }
+}
+
+impl PartialOrd for NaiveLunisolarDateTime {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
 impl NaiveLunisolarDateTime {
     pub fn new_from_datetime(datetime: DateTime<FixedOffset>) -> Result<NaiveLunisolarDateTime>{
         let hour = datetime.hour();
         let minute = datetime.minute();
 
-        let (day, month, year) = NaiveLunisolarDateTime::datetime_to_lunisolar(datetime)?;
+        let (day, month, year, leap) = NaiveLunisolarDateTime::datetime_to_lunisolar(datetime)?;
 
         Ok(NaiveLunisolarDateTime{
             hour_lunisolar: hour,
             minute_lunisolar: minute,
             day_lunisolar: day,
             month_lunisolar: month,
+            leap_lunisolar: leap,
             year_lunisolar: year,
         })
     }
 
-    fn datetime_to_lunisolar(datetime: DateTime<FixedOffset>) -> Result<(u32, u32, u32)> {
+    fn datetime_to_lunisolar(datetime: DateTime<FixedOffset>) -> Result<(u32, u32, u32, bool)> {
         let day = datetime.day();
         let month = datetime.month();
         let year_i32 = datetime.year();
-        if year_i32 < MIN_YEAR || year_i32 > MAX_YEAR {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year_i32) {
             bail!("year should be between {} and {}", MIN_YEAR, MAX_YEAR);
         }
 
         let time_zone = datetime.timezone().local_minus_utc() as f64 / 3600_f64;
-        let (d, m, y) = NaiveLunisolarDateTime::convert_solar_to_lunisolar(day, month, year_i32, time_zone);
-        Ok((d, m, y as u32))
+        let (d, m, y, leap) = NaiveLunisolarDateTime::convert_solar_to_lunisolar(day, month, year_i32, time_zone);
+        Ok((d, m, y as u32, leap))
     }
-    fn jd_from_date(dd: u32, mm: u32, yy: i32) -> i32 {
+    pub(crate) fn jd_from_date(dd: u32, mm: u32, yy: i32) -> i32 {
         let a = ((14 - mm) / 12) as i32;
         let y = yy + 4800 - a;
         let m = mm + 12 * a as u32 - 3;
-        let jd = dd as i32 + ((153 * m + 2) / 5) as i32 + 365 * y + (y / 4) - (y / 100) + (y / 400) as i32 - 32045;
+        let jd = dd as i32 + ((153 * m + 2) / 5) as i32 + 365 * y + (y / 4) - (y / 100) + (y / 400) - 32045;
 
         if jd < 2299161 {
             return dd as i32 + ((153 * m + 2) / 5) as i32 + 365 * y + (y / 4) - 32083;
@@ -53,23 +78,22 @@ impl NaiveLunisolarDateTime {
         jd
     }
 
-    #[allow(dead_code)]
     fn jd_to_date(jd: i32) -> (u32, u32, i32) {
         // Check if the Julian day is after October 4, 1582 (the date of the Gregorian calendar switch)
         let is_gregorian = jd > 2299160;
 
-        return if is_gregorian {
+        if is_gregorian {
             // Gregorian calendar
             let a = jd + 32044;
-            let b = ((4 * a + 3) / 146097) as i32;
-            let c = a - ((b * 146097) / 4) as i32;
+            let b = (4 * a + 3) / 146097;
+            let c = a - ((b * 146097) / 4);
 
-            let d = ((4 * c + 3) / 1461) as i32;
-            let e = c - ((1461 * d) / 4) as i32;
-            let m = ((5 * e + 2) / 153) as i32;
+            let d = (4 * c + 3) / 1461;
+            let e = c - ((1461 * d) / 4);
+            let m = (5 * e + 2) / 153;
             let day = e - ((153 * m + 2) / 5) + 1;
-            let month = m + 3 - 12 * ((m / 10) as i32);
-            let year = b * 100 + d - 4800 + ((m / 10) as i32);
+            let month = m + 3 - 12 * (m / 10);
+            let year = b * 100 + d - 4800 + (m / 10);
 
             (day as u32, month as u32, year)
         } else {
@@ -78,15 +102,15 @@ impl NaiveLunisolarDateTime {
             let b = 0;
             let c = a;
 
-            let d = ((4 * c + 3) / 1461) as i32;
-            let e = c - ((1461 * d) / 4) as i32;
-            let m = ((5 * e + 2) / 153) as i32;
+            let d = (4 * c + 3) / 1461;
+            let e = c - ((1461 * d) / 4);
+            let m = (5 * e + 2) / 153;
             let day = e - ((153 * m + 2) / 5) + 1;
-            let month = m + 3 - 12 * ((m / 10) as i32);
-            let year = b * 100 + d - 4800 + ((m / 10) as i32);
+            let month = m + 3 - 12 * (m / 10);
+            let year = b * 100 + d - 4800 + (m / 10);
 
             (day as u32, month as u32, year)
-        };
+        }
     }
 
     fn get_new_moon_day(k_int: i32, time_zone: f64) -> i32 {
@@ -131,7 +155,7 @@ impl NaiveLunisolarDateTime {
         (jd_new + 0.5 + time_zone / 24_f64) as i32
     }
 
-    fn get_sun_longitude(jdn: f64, time_zone: f64) -> f64 {
+    pub(crate) fn get_sun_longitude(jdn: f64, time_zone: f64) -> f64 {
         const DR: f64 = std::f64::consts::PI / 180.0;
 
         let t = (jdn - 2451545.5 - time_zone / 24.0) / 36525.0; // Time in Julian centuries from 2000-01-01 12:00:00 GMT
@@ -186,7 +210,7 @@ impl NaiveLunisolarDateTime {
         i - 1
     }
 
-    fn convert_solar_to_lunisolar(dd: u32, mm: u32, yy: i32, time_zone: f64) -> (u32, u32, i32) {
+    fn convert_solar_to_lunisolar(dd: u32, mm: u32, yy: i32, time_zone: f64) -> (u32, u32, i32, bool) {
         let day_number = NaiveLunisolarDateTime::jd_from_date(dd, mm, yy);
         let k = ((day_number as f64 - 2415021.076998695) / 29.530588853) as i32;
         let mut month_start = NaiveLunisolarDateTime::get_new_moon_day(k + 1, time_zone);
@@ -201,7 +225,7 @@ impl NaiveLunisolarDateTime {
         let mut lunisolar_year :i32;
         let mut lunisolar_month :i32;
         let lunisolar_day = day_number - month_start + 1;
-        // let mut lunisolar_leap: bool;
+        let mut lunisolar_leap = false;
 
         if a11 >= month_start {
             lunisolar_year = yy;
@@ -217,11 +241,11 @@ impl NaiveLunisolarDateTime {
         lunisolar_month = diff + 11;
 
         if b11 - a11 > 365 {
-            let leap_month_diff = NaiveLunisolarDateTime::get_leap_month_offset(a11 as f64, time_zone) as i32;
+            let leap_month_diff = NaiveLunisolarDateTime::get_leap_month_offset(a11 as f64, time_zone);
 
             if diff >= leap_month_diff {
                 lunisolar_month = diff + 10;
-                // lunisolar_leap = diff == leap_month_diff;
+                lunisolar_leap = diff == leap_month_diff;
             }
         }
 
@@ -233,45 +257,80 @@ impl NaiveLunisolarDateTime {
             lunisolar_year -= 1;
         }
 
-        (lunisolar_day as u32, lunisolar_month as u32, lunisolar_year)
+        (lunisolar_day as u32, lunisolar_month as u32, lunisolar_year, lunisolar_leap)
     }
 
-    #[allow(dead_code)]
-    fn convert_lunisolar_to_solar(lunisolar_day: u32, lunisolar_month: u32, lunisolar_year: i32, lunisolar_leap: bool, time_zone: f64) -> (u32, u32, i32) {
-        let a11: i32;
-        // let b11: i32;
-
-        if lunisolar_month < 11 {
-            a11 = NaiveLunisolarDateTime::get_lunisolar_month_11(lunisolar_year - 1, time_zone);
-            //b11 = NaiveLunisolarDateTime::get_lunisolar_month_11(lunisolar_year, time_zone);
+    // Index of the new moon opening the lunisolar month, None when the requested leap month does not exist
+    fn lunisolar_month_new_moon(lunisolar_month: u32, lunisolar_year: i32, lunisolar_leap: bool, time_zone: f64) -> Option<i32> {
+        let (a11, b11) = if lunisolar_month < 11 {
+            (NaiveLunisolarDateTime::get_lunisolar_month_11(lunisolar_year - 1, time_zone),
+             NaiveLunisolarDateTime::get_lunisolar_month_11(lunisolar_year, time_zone))
         } else {
-            a11 = NaiveLunisolarDateTime::get_lunisolar_month_11(lunisolar_year, time_zone);
-           //b11 = NaiveLunisolarDateTime::get_lunisolar_month_11(lunisolar_year + 1, time_zone);
-        }
+            (NaiveLunisolarDateTime::get_lunisolar_month_11(lunisolar_year, time_zone),
+             NaiveLunisolarDateTime::get_lunisolar_month_11(lunisolar_year + 1, time_zone))
+        };
 
+        let k = ((a11 as f64 - 2415021.076998695) / 29.530588853 + 0.5) as i32;
         let mut off = lunisolar_month as i32 - 11;
         if off < 0 {
             off += 12;
         }
 
-        let leap_off = NaiveLunisolarDateTime::get_leap_month_offset(a11 as f64, time_zone) as i32;
-        let mut leap_month = leap_off - 2;
+        if b11 - a11 > 365 {
+            let leap_off = NaiveLunisolarDateTime::get_leap_month_offset(a11 as f64, time_zone);
+            let leap_month = (leap_off + 9) % 12 + 1;
 
-        if leap_month < 0 {
-            leap_month += 12;
+            if lunisolar_leap && lunisolar_month != leap_month as u32 {
+                return None;
+            } else if lunisolar_leap || off >= leap_off {
+                off += 1;
+            }
+        } else if lunisolar_leap {
+            return None;
         }
 
-        if lunisolar_leap && lunisolar_month != leap_month as u32 {
-            return (0, 0, 0);
-        } else if lunisolar_leap || off >= leap_off {
-            off += 1;
+        Some(k + off)
+    }
+
+    fn convert_lunisolar_to_solar(lunisolar_day: u32, lunisolar_month: u32, lunisolar_year: i32, lunisolar_leap: bool, time_zone: f64) -> (u32, u32, i32) {
+        match NaiveLunisolarDateTime::lunisolar_month_new_moon(lunisolar_month, lunisolar_year, lunisolar_leap, time_zone) {
+            Some(new_moon) => {
+                let month_start = NaiveLunisolarDateTime::get_new_moon_day(new_moon, time_zone);
+                NaiveLunisolarDateTime::jd_to_date(month_start + lunisolar_day as i32 - 1)
+            }
+            None => (0, 0, 0),
         }
+    }
 
-        let k = ((a11 as f64 - 2415021.076998695) / 29.530588853 + 0.5) as i32;
-        let month_start = NaiveLunisolarDateTime::get_new_moon_day(k + off, time_zone);
+    // 29 or 30
+    pub fn days_in_lunisolar_month(month: u32, year: i32, leap: bool, time_zone: f64) -> Result<u32> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            bail!("year should be between {} and {}", MIN_YEAR, MAX_YEAR);
+        }
+        if !(1..=12).contains(&month) {
+            bail!("month should be between 1 and 12");
+        }
+        match NaiveLunisolarDateTime::lunisolar_month_new_moon(month, year, leap, time_zone) {
+            Some(new_moon) => {
+                let month_start = NaiveLunisolarDateTime::get_new_moon_day(new_moon, time_zone);
+                let next_month_start = NaiveLunisolarDateTime::get_new_moon_day(new_moon + 1, time_zone);
+                Ok((next_month_start - month_start) as u32)
+            }
+            None => bail!("year {} has no leap month {}", year, month),
+        }
+    }
+
+    pub fn leap_month_of_year(year: i32, time_zone: f64) -> Option<u32> {
+        (1..=12).find(|month| NaiveLunisolarDateTime::lunisolar_month_new_moon(*month, year, true, time_zone).is_some())
+    }
 
-        let solar_day = month_start + lunisolar_day as i32 - 1;
-        NaiveLunisolarDateTime::jd_to_date(solar_day)
+    pub fn lunisolar_to_gregorian(day: u32, month: u32, year: i32, leap: bool, time_zone: f64) -> Result<NaiveDate> {
+        let days_in_month = NaiveLunisolarDateTime::days_in_lunisolar_month(month, year, leap, time_zone)?;
+        if day < 1 || day > days_in_month {
+            bail!("lunisolar month {}/{} has {} days", month, year, days_in_month);
+        }
+        let (d, m, y) = NaiveLunisolarDateTime::convert_lunisolar_to_solar(day, month, year, leap, time_zone);
+        NaiveDate::from_ymd_opt(y, m, d).ok_or_else(|| anyhow!("invalid gregorian date {}-{}-{}", y, m, d))
     }
 }
 
@@ -279,7 +338,7 @@ impl NaiveLunisolarDateTime {
 mod tests {
     use rstest::rstest;
     use super::*;
-    use chrono::{NaiveTime, NaiveDate, NaiveDateTime};
+    use chrono::{NaiveTime, NaiveDateTime};
 
 
     #[rstest]
@@ -307,7 +366,7 @@ mod tests {
 
 
         match NaiveLunisolarDateTime::datetime_to_lunisolar(datetime) {
-            Ok((day, month, year)) => {
+            Ok((day, month, year, _)) => {
                 assert_eq!(day, expected_dd);
                 assert_eq!(month, expected_mm);
                 assert_eq!(year, expected_yyyy);
@@ -319,4 +378,63 @@ mod tests {
             }
         };
     }
-}
\ No newline at end of file
+
+    #[rstest]
+    #[case(1, 1, 2024, false, 2024, 2, 10)]
+    #[case(15, 8, 2023, false, 2023, 9, 29)]
+    #[case(1, 2, 2023, true, 2023, 3, 22)]
+    #[case(6, 12, 2010, false, 2011, 1, 9)]
+    fn test_lunisolar_to_gregorian(#[case] dd: u32, #[case] mm: u32, #[case] yyyy: i32, #[case] leap: bool,
+                                   #[case] expected_yyyy: i32, #[case] expected_mm: u32, #[case] expected_dd: u32) {
+        let date = NaiveLunisolarDateTime::lunisolar_to_gregorian(dd, mm, yyyy, leap, 7.0).unwrap();
+        assert_eq!(date, NaiveDate::from_ymd_opt(expected_yyyy, expected_mm, expected_dd).unwrap());
+    }
+
+    #[rstest]
+    #[case(2023, 3, 21, 2, false)]
+    #[case(2023, 3, 22, 2, true)]
+    #[case(2023, 4, 19, 2, true)]
+    #[case(2023, 4, 20, 3, false)]
+    fn test_leap_tracking(#[case] yyyy: i32, #[case] mm: u32, #[case] dd: u32,
+                          #[case] expected_mm: u32, #[case] expected_leap: bool) {
+        let offset = FixedOffset::east_opt(7 * 3600).unwrap();
+        let datetime = NaiveDate::from_ymd_opt(yyyy, mm, dd).unwrap().and_hms_opt(12, 0, 0).unwrap()
+            .and_local_timezone(offset).unwrap();
+        let lunisolar = NaiveLunisolarDateTime::new_from_datetime(datetime).unwrap();
+        assert_eq!(lunisolar.month_lunisolar, expected_mm);
+        assert_eq!(lunisolar.leap_lunisolar, expected_leap);
+    }
+
+    #[test]
+    fn test_leap_month_and_month_length() {
+        assert_eq!(NaiveLunisolarDateTime::leap_month_of_year(2023, 7.0), Some(2));
+        assert_eq!(NaiveLunisolarDateTime::leap_month_of_year(2020, 7.0), Some(4));
+        assert_eq!(NaiveLunisolarDateTime::leap_month_of_year(2024, 7.0), None);
+        assert!(NaiveLunisolarDateTime::lunisolar_to_gregorian(1, 3, 2023, true, 7.0).is_err());
+        assert!(NaiveLunisolarDateTime::lunisolar_to_gregorian(1, 2, 2024, true, 7.0).is_err());
+
+        // Quý Mão ends on the 30th, Giáp Thìn has no 30 Tết
+        assert_eq!(NaiveLunisolarDateTime::days_in_lunisolar_month(12, 2023, false, 7.0).unwrap(), 30);
+        assert_eq!(NaiveLunisolarDateTime::days_in_lunisolar_month(12, 2024, false, 7.0).unwrap(), 29);
+        assert!(NaiveLunisolarDateTime::lunisolar_to_gregorian(30, 12, 2024, false, 7.0).is_err());
+    }
+
+    #[test]
+    fn test_chronological_order() {
+        let date = |day, month, leap, year| NaiveLunisolarDateTime {
+            hour_lunisolar: 0,
+            minute_lunisolar: 0,
+            day_lunisolar: day,
+            month_lunisolar: month,
+            leap_lunisolar: leap,
+            year_lunisolar: year,
+        };
+        // The leap second month of Quý Mão falls between the regular second and third months
+        let mut dates = vec![date(1, 3, false, 2023), date(29, 2, false, 2023), date(1, 2, true, 2023), date(30, 12, false, 2022)];
+        dates.sort();
+        assert_eq!(dates, vec![date(30, 12, false, 2022), date(29, 2, false, 2023), date(1, 2, true, 2023), date(1, 3, false, 2023)]);
+
+        let index: std::collections::HashSet<NaiveLunisolarDateTime> = dates.iter().copied().chain(dates.iter().copied()).collect();
+        assert_eq!(index.len(), 4);
+    }
+}
@@ -0,0 +1,148 @@
+use anyhow::Result;
+use chrono::{Datelike, NaiveDate};
+use super::lunisolar_datetime::*;
+
+// How an event that happened in a leap month recurs
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LeapMonthPolicy {
+    // Observe in the regular month of the same number every year
+    RegularMonth,
+    // Observe in the leap month when the year repeats it, in the regular month otherwise
+    LeapMonthWhenPresent,
+    // Observe only in years repeating the same leap month
+    OnlyLeapMonth,
+}
+
+// How a 30th recurs in a year where the month only has 29 days
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MissingDayPolicy {
+    // Observe on the 29th
+    LastDayOfMonth,
+    // Observe on the 1st of the following month
+    FirstDayOfNextMonth,
+    // No observance that year
+    Skip,
+}
+
+// A lunisolar day recurring every year, such as a ngày giỗ
+#[derive(Clone, Debug, PartialEq)]
+pub struct LunisolarRecurrence {
+    pub day: u32,
+    pub month: u32,
+    pub leap: bool,
+    pub time_zone: f64,
+    pub leap_month_policy: LeapMonthPolicy,
+    pub missing_day_policy: MissingDayPolicy,
+}
+
+impl LunisolarRecurrence {
+    pub fn new(day: u32, month: u32, leap: bool, time_zone: f64) -> LunisolarRecurrence {
+        LunisolarRecurrence {
+            day,
+            month,
+            leap,
+            time_zone,
+            leap_month_policy: LeapMonthPolicy::RegularMonth,
+            missing_day_policy: MissingDayPolicy::LastDayOfMonth,
+        }
+    }
+
+    pub fn from_lunisolar(date: &NaiveLunisolarDateTime, time_zone: f64) -> LunisolarRecurrence {
+        LunisolarRecurrence::new(date.day_lunisolar, date.month_lunisolar, date.leap_lunisolar, time_zone)
+    }
+
+    // Gregorian date of the observance in the lunisolar year, None when the policies skip that year
+    pub fn occurrence_in_lunisolar_year(&self, year: i32) -> Result<Option<NaiveDate>> {
+        let year_has_leap = NaiveLunisolarDateTime::leap_month_of_year(year, self.time_zone) == Some(self.month);
+        let leap = match (self.leap, &self.leap_month_policy) {
+            (false, _) | (true, LeapMonthPolicy::RegularMonth) => false,
+            (true, LeapMonthPolicy::LeapMonthWhenPresent) => year_has_leap,
+            (true, LeapMonthPolicy::OnlyLeapMonth) => {
+                if !year_has_leap {
+                    return Ok(None);
+                }
+                true
+            }
+        };
+
+        let days_in_month = NaiveLunisolarDateTime::days_in_lunisolar_month(self.month, year, leap, self.time_zone)?;
+        if self.day <= days_in_month {
+            return NaiveLunisolarDateTime::lunisolar_to_gregorian(self.day, self.month, year, leap, self.time_zone).map(Some);
+        }
+        let last_day = NaiveLunisolarDateTime::lunisolar_to_gregorian(days_in_month, self.month, year, leap, self.time_zone)?;
+        Ok(match self.missing_day_policy {
+            MissingDayPolicy::LastDayOfMonth => Some(last_day),
+            MissingDayPolicy::FirstDayOfNextMonth => last_day.succ_opt(),
+            MissingDayPolicy::Skip => None,
+        })
+    }
+
+    // The next `count` observances on or after the date, fewer when the supported year range runs out
+    pub fn next_occurrences(&self, from: NaiveDate, count: usize) -> Result<Vec<NaiveDate>> {
+        let mut result = Vec::new();
+        let mut year = from.year() - 1;
+        while result.len() < count && year <= MAX_YEAR {
+            if year >= MIN_YEAR {
+                if let Some(date) = self.occurrence_in_lunisolar_year(year)? {
+                    if date >= from {
+                        result.push(date);
+                    }
+                }
+            }
+            year += 1;
+        }
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(yyyy: i32, mm: u32, dd: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(yyyy, mm, dd).unwrap()
+    }
+
+    #[test]
+    fn test_yearly_occurrences() {
+        let gio = LunisolarRecurrence::new(15, 8, false, 7.0);
+        assert_eq!(gio.next_occurrences(ymd(2023, 1, 1), 3).unwrap(),
+                   vec![ymd(2023, 9, 29), ymd(2024, 9, 17), ymd(2025, 10, 6)]);
+        assert_eq!(gio.next_occurrences(ymd(2024, 9, 17), 1).unwrap(), vec![ymd(2024, 9, 17)]);
+        assert_eq!(gio.next_occurrences(ymd(2100, 1, 1), 5).unwrap().len(), 1);
+    }
+
+    #[test]
+    fn test_missing_30th() {
+        // Quý Mão has a 30th of the twelfth month, Giáp Thìn does not
+        let mut gio = LunisolarRecurrence::new(30, 12, false, 7.0);
+        assert_eq!(gio.next_occurrences(ymd(2024, 1, 1), 2).unwrap(), vec![ymd(2024, 2, 9), ymd(2025, 1, 28)]);
+
+        gio.missing_day_policy = MissingDayPolicy::FirstDayOfNextMonth;
+        assert_eq!(gio.next_occurrences(ymd(2024, 1, 1), 2).unwrap(), vec![ymd(2024, 2, 9), ymd(2025, 1, 29)]);
+
+        gio.missing_day_policy = MissingDayPolicy::Skip;
+        assert_eq!(gio.occurrence_in_lunisolar_year(2024).unwrap(), None);
+    }
+
+    #[test]
+    fn test_leap_month() {
+        // 15 of the leap second month of Quý Mão
+        let offset = chrono::FixedOffset::east_opt(7 * 3600).unwrap();
+        let datetime = ymd(2023, 4, 5).and_hms_opt(12, 0, 0).unwrap().and_local_timezone(offset).unwrap();
+        let lunisolar = NaiveLunisolarDateTime::new_from_datetime(datetime).unwrap();
+        let mut gio = LunisolarRecurrence::from_lunisolar(&lunisolar, 7.0);
+        assert!(gio.leap);
+
+        let regular_2023 = NaiveLunisolarDateTime::lunisolar_to_gregorian(15, 2, 2023, false, 7.0).unwrap();
+        let regular_2024 = NaiveLunisolarDateTime::lunisolar_to_gregorian(15, 2, 2024, false, 7.0).unwrap();
+        assert_eq!(gio.occurrence_in_lunisolar_year(2023).unwrap(), Some(regular_2023));
+
+        gio.leap_month_policy = LeapMonthPolicy::LeapMonthWhenPresent;
+        assert_eq!(gio.occurrence_in_lunisolar_year(2023).unwrap(), Some(ymd(2023, 4, 5)));
+        assert_eq!(gio.occurrence_in_lunisolar_year(2024).unwrap(), Some(regular_2024));
+
+        gio.leap_month_policy = LeapMonthPolicy::OnlyLeapMonth;
+        assert_eq!(gio.occurrence_in_lunisolar_year(2024).unwrap(), None);
+    }
+}
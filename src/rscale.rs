@@ -0,0 +1,439 @@
+use anyhow::{Result, anyhow, bail};
+use chrono::{FixedOffset, NaiveDate};
+use super::calendar_system::*;
+use super::lunisolar_datetime::*;
+
+// FREQ values evaluated in the lunisolar calendar
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Frequency {
+    Yearly,
+    Monthly,
+}
+
+// RFC 7529 SKIP, applied to a leap month or day the calendar lacks in a given year
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Skip {
+    Omit,
+    // Leap month falls back to the regular month, day 30 to day 29
+    Backward,
+    // Leap month moves to the following month, day 30 to the 1st of the following month
+    Forward,
+}
+
+// A BYMONTH entry such as "5" or "5L"
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RuleMonth {
+    pub month: u32,
+    pub leap: bool,
+}
+
+impl RuleMonth {
+    pub fn new(month: u32, leap: bool) -> RuleMonth {
+        RuleMonth { month, leap }
+    }
+
+    fn parse(value: &str) -> Result<RuleMonth> {
+        let (number, leap) = match value.strip_suffix('L') {
+            Some(number) => (number, true),
+            None => (value, false),
+        };
+        let month: u32 = number.parse().map_err(|_| anyhow!("invalid BYMONTH value {}", value))?;
+        if !(1..=12).contains(&month) {
+            bail!("BYMONTH value {} is out of range", value);
+        }
+        Ok(RuleMonth::new(month, leap))
+    }
+}
+
+// An RRULE with RSCALE=CHINESE, VIETNAMESE or KOREAN (also spelled DANGI)
+#[derive(Clone, Debug, PartialEq)]
+pub struct RecurrenceRule {
+    pub calendar: CalendarSystem,
+    pub frequency: Frequency,
+    pub interval: u32,
+    pub count: Option<usize>,
+    pub until: Option<NaiveDate>,
+    pub by_month: Vec<RuleMonth>,
+    // Negative values count back from the end of the month
+    pub by_month_day: Vec<i32>,
+    pub skip: Skip,
+}
+
+fn calendar_from_rscale(value: &str) -> Result<CalendarSystem> {
+    match value.to_ascii_uppercase().as_str() {
+        "CHINESE" => Ok(CalendarSystem::Chinese),
+        "VIETNAMESE" => Ok(CalendarSystem::Vietnamese),
+        "KOREAN" | "DANGI" => Ok(CalendarSystem::Korean),
+        _ => bail!("unsupported RSCALE {}", value),
+    }
+}
+
+// The inverse of calendar_from_rscale, so every rule written can be parsed back
+fn rscale_name(calendar: &CalendarSystem) -> Result<&'static str> {
+    match calendar {
+        CalendarSystem::Vietnamese => Ok("VIETNAMESE"),
+        CalendarSystem::Chinese => Ok("CHINESE"),
+        CalendarSystem::Korean => Ok("KOREAN"),
+        CalendarSystem::Japanese => bail!("RSCALE=JAPANESE is not a lunisolar calendar"),
+    }
+}
+
+// UNTIL is either a DATE or a DATE-TIME, only the date part matters for all-day occurrences
+fn parse_until(value: &str) -> Result<NaiveDate> {
+    let date = value.get(..8).ok_or_else(|| anyhow!("invalid UNTIL value {}", value))?;
+    NaiveDate::parse_from_str(date, "%Y%m%d").map_err(|_| anyhow!("invalid UNTIL value {}", value))
+}
+
+impl RecurrenceRule {
+    pub fn new(calendar: CalendarSystem, frequency: Frequency) -> RecurrenceRule {
+        RecurrenceRule {
+            calendar,
+            frequency,
+            interval: 1,
+            count: None,
+            until: None,
+            by_month: Vec::new(),
+            by_month_day: Vec::new(),
+            skip: Skip::Omit,
+        }
+    }
+
+    // Accepts the value with or without the "RRULE:" prefix, e.g. "RSCALE=CHINESE;FREQ=YEARLY;BYMONTH=5L;SKIP=FORWARD"
+    pub fn parse(rule: &str) -> Result<RecurrenceRule> {
+        let rule = rule.trim();
+        let rule = rule.strip_prefix("RRULE:").unwrap_or(rule);
+        let mut calendar = None;
+        let mut frequency = None;
+        let mut result = RecurrenceRule::new(CalendarSystem::Chinese, Frequency::Yearly);
+        let mut skip = None;
+        for part in rule.split(';').filter(|part| !part.is_empty()) {
+            let (name, value) = part.split_once('=').ok_or_else(|| anyhow!("invalid rule part {}", part))?;
+            match name.to_ascii_uppercase().as_str() {
+                "RSCALE" => calendar = Some(calendar_from_rscale(value)?),
+                "FREQ" => frequency = Some(match value.to_ascii_uppercase().as_str() {
+                    "YEARLY" => Frequency::Yearly,
+                    "MONTHLY" => Frequency::Monthly,
+                    _ => bail!("unsupported FREQ {}", value),
+                }),
+                "INTERVAL" => {
+                    result.interval = value.parse().map_err(|_| anyhow!("invalid INTERVAL {}", value))?;
+                    if result.interval == 0 {
+                        bail!("INTERVAL should be positive");
+                    }
+                }
+                "COUNT" => result.count = Some(value.parse().map_err(|_| anyhow!("invalid COUNT {}", value))?),
+                "UNTIL" => result.until = Some(parse_until(value)?),
+                "BYMONTH" => {
+                    result.by_month = value.split(',').map(RuleMonth::parse).collect::<Result<_>>()?;
+                }
+                "BYMONTHDAY" => {
+                    result.by_month_day = value.split(',')
+                        .map(|day| match day.parse::<i32>() {
+                            Ok(day) if day != 0 && (-30..=30).contains(&day) => Ok(day),
+                            _ => Err(anyhow!("invalid BYMONTHDAY value {}", day)),
+                        })
+                        .collect::<Result<_>>()?;
+                }
+                "SKIP" => skip = Some(match value.to_ascii_uppercase().as_str() {
+                    "OMIT" => Skip::Omit,
+                    "BACKWARD" => Skip::Backward,
+                    "FORWARD" => Skip::Forward,
+                    _ => bail!("invalid SKIP {}", value),
+                }),
+                "WKST" => {}
+                _ => bail!("unsupported rule part {}", name),
+            }
+        }
+        result.calendar = calendar.ok_or_else(|| anyhow!("RSCALE is required"))?;
+        result.frequency = frequency.ok_or_else(|| anyhow!("FREQ is required"))?;
+        if result.count.is_some() && result.until.is_some() {
+            bail!("COUNT and UNTIL cannot both be set");
+        }
+        // A step past the supported years would never produce a second occurrence
+        let max_interval = match result.frequency {
+            Frequency::Yearly => (MAX_YEAR - MIN_YEAR) as u32,
+            Frequency::Monthly => (MAX_YEAR - MIN_YEAR) as u32 * 13,
+        };
+        if result.interval > max_interval {
+            bail!("INTERVAL {} is longer than the supported years", result.interval);
+        }
+        if let Some(skip) = skip {
+            result.skip = skip;
+        }
+        Ok(result)
+    }
+
+    pub fn to_rrule(&self) -> Result<String> {
+        let mut parts = vec![
+            format!("RSCALE={}", rscale_name(&self.calendar)?),
+            format!("FREQ={}", match self.frequency {
+                Frequency::Yearly => { "YEARLY" }
+                Frequency::Monthly => { "MONTHLY" }
+            }),
+        ];
+        if self.interval != 1 {
+            parts.push(format!("INTERVAL={}", self.interval));
+        }
+        if let Some(count) = self.count {
+            parts.push(format!("COUNT={}", count));
+        }
+        if let Some(until) = self.until {
+            parts.push(format!("UNTIL={}", until.format("%Y%m%d")));
+        }
+        if !self.by_month.is_empty() {
+            let months: Vec<String> = self.by_month.iter()
+                .map(|month| format!("{}{}", month.month, if month.leap { "L" } else { "" }))
+                .collect();
+            parts.push(format!("BYMONTH={}", months.join(",")));
+        }
+        if !self.by_month_day.is_empty() {
+            let days: Vec<String> = self.by_month_day.iter().map(|day| day.to_string()).collect();
+            parts.push(format!("BYMONTHDAY={}", days.join(",")));
+        }
+        if self.skip != Skip::Omit {
+            parts.push(format!("SKIP={}", match self.skip {
+                Skip::Omit => { "OMIT" }
+                Skip::Backward => { "BACKWARD" }
+                Skip::Forward => { "FORWARD" }
+            }));
+        }
+        Ok(parts.join(";"))
+    }
+
+    // Occurrences starting at DTSTART, which is always the first one, stopping at COUNT, UNTIL,
+    // `limit` or the end of the supported year range
+    pub fn occurrences(&self, dtstart: NaiveDate, limit: usize) -> Result<Vec<NaiveDate>> {
+        if self.calendar == CalendarSystem::Japanese {
+            bail!("RSCALE=JAPANESE is not a lunisolar calendar");
+        }
+        let time_zone = self.calendar.time_zone();
+        let offset = FixedOffset::east_opt((time_zone * 3600.0) as i32).unwrap();
+        let start = NaiveLunisolarDateTime::new_from_datetime(
+            dtstart.and_hms_opt(12, 0, 0).unwrap().and_local_timezone(offset).unwrap())?;
+        let start_year = start.year_lunisolar as i32;
+        let start_month = RuleMonth::new(start.month_lunisolar, start.leap_lunisolar);
+        let days = if self.by_month_day.is_empty() { vec![start.day_lunisolar as i32] } else { self.by_month_day.clone() };
+        let limit = self.count.map_or(limit, |count| count.min(limit));
+
+        let mut result = vec![dtstart];
+        let push_period = |mut dates: Vec<NaiveDate>, result: &mut Vec<NaiveDate>| -> bool {
+            dates.sort();
+            dates.dedup();
+            for date in dates.into_iter().filter(|date| *date > dtstart) {
+                if result.len() >= limit || self.until.is_some_and(|until| date > until) {
+                    return false;
+                }
+                result.push(date);
+            }
+            true
+        };
+
+        match self.frequency {
+            Frequency::Yearly => {
+                let months = if self.by_month.is_empty() { vec![start_month] } else { self.by_month.clone() };
+                let step = i32::try_from(self.interval).map_err(|_| anyhow!("INTERVAL {} is too large", self.interval))?;
+                let mut year = start_year;
+                while (MIN_YEAR..=MAX_YEAR).contains(&year) {
+                    let mut dates = Vec::new();
+                    for month in &months {
+                        if let Some((month, year)) = self.resolve_month(month, year, time_zone) {
+                            for day in &days {
+                                dates.extend(self.resolve_day(*day, &month, year, time_zone)?);
+                            }
+                        }
+                    }
+                    if !push_period(dates, &mut result) {
+                        break;
+                    }
+                    match year.checked_add(step) {
+                        Some(next) => year = next,
+                        None => break,
+                    }
+                }
+            }
+            Frequency::Monthly => {
+                let (mut month, mut year) = (start_month, start_year);
+                let mut index = 0;
+                while year <= MAX_YEAR {
+                    if index % self.interval == 0 && (self.by_month.is_empty() || self.by_month.contains(&month)) {
+                        let mut dates = Vec::new();
+                        for day in &days {
+                            dates.extend(self.resolve_day(*day, &month, year, time_zone)?);
+                        }
+                        if !push_period(dates, &mut result) {
+                            break;
+                        }
+                    }
+                    (month, year) = next_month(&month, year, time_zone);
+                    index += 1;
+                }
+            }
+        }
+        if result.len() > limit {
+            result.truncate(limit);
+        }
+        if self.until.is_some_and(|until| dtstart > until) {
+            result.clear();
+        }
+        Ok(result)
+    }
+
+    // The month actually observed in the year, None when SKIP=OMIT drops a missing leap month
+    fn resolve_month(&self, month: &RuleMonth, year: i32, time_zone: f64) -> Option<(RuleMonth, i32)> {
+        if !month.leap || NaiveLunisolarDateTime::leap_month_of_year(year, time_zone) == Some(month.month) {
+            return Some((*month, year));
+        }
+        match self.skip {
+            Skip::Omit => None,
+            Skip::Backward => Some((RuleMonth::new(month.month, false), year)),
+            Skip::Forward if month.month == 12 => Some((RuleMonth::new(1, false), year + 1)),
+            Skip::Forward => Some((RuleMonth::new(month.month + 1, false), year)),
+        }
+    }
+
+    fn resolve_day(&self, day: i32, month: &RuleMonth, year: i32, time_zone: f64) -> Result<Option<NaiveDate>> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            return Ok(None);
+        }
+        let days_in_month = NaiveLunisolarDateTime::days_in_lunisolar_month(month.month, year, month.leap, time_zone)? as i32;
+        let day = if day < 0 { days_in_month + 1 + day } else { day };
+        if (1..=days_in_month).contains(&day) {
+            return NaiveLunisolarDateTime::lunisolar_to_gregorian(day as u32, month.month, year, month.leap, time_zone).map(Some);
+        }
+        if day < 1 {
+            return Ok(None);
+        }
+        let last_day = NaiveLunisolarDateTime::lunisolar_to_gregorian(days_in_month as u32, month.month, year, month.leap, time_zone)?;
+        Ok(match self.skip {
+            Skip::Omit => None,
+            Skip::Backward => Some(last_day),
+            Skip::Forward => last_day.succ_opt(),
+        })
+    }
+}
+
+// Month following in the calendar, a leap month comes right after the regular month of the same number
+fn next_month(month: &RuleMonth, year: i32, time_zone: f64) -> (RuleMonth, i32) {
+    if !month.leap && NaiveLunisolarDateTime::leap_month_of_year(year, time_zone) == Some(month.month) {
+        (RuleMonth::new(month.month, true), year)
+    } else if month.month == 12 {
+        (RuleMonth::new(1, false), year + 1)
+    } else {
+        (RuleMonth::new(month.month + 1, false), year)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ymd(yyyy: i32, mm: u32, dd: u32) -> NaiveDate {
+        NaiveDate::from_ymd_opt(yyyy, mm, dd).unwrap()
+    }
+
+    fn lunisolar(day: u32, month: u32, year: i32, leap: bool, calendar: &CalendarSystem) -> NaiveDate {
+        NaiveLunisolarDateTime::lunisolar_to_gregorian(day, month, year, leap, calendar.time_zone()).unwrap()
+    }
+
+    #[test]
+    fn test_parse() {
+        let rule = RecurrenceRule::parse("RRULE:RSCALE=CHINESE;FREQ=YEARLY;BYMONTH=2L,3;BYMONTHDAY=-1;SKIP=BACKWARD;COUNT=4").unwrap();
+        assert_eq!(rule.calendar, CalendarSystem::Chinese);
+        assert_eq!(rule.by_month, vec![RuleMonth::new(2, true), RuleMonth::new(3, false)]);
+        assert_eq!(rule.by_month_day, vec![-1]);
+        assert_eq!(rule.skip, Skip::Backward);
+        assert_eq!(rule.count, Some(4));
+        assert_eq!(rule.to_rrule().unwrap(), "RSCALE=CHINESE;FREQ=YEARLY;COUNT=4;BYMONTH=2L,3;BYMONTHDAY=-1;SKIP=BACKWARD");
+        for calendar in [CalendarSystem::Vietnamese, CalendarSystem::Chinese, CalendarSystem::Korean] {
+            let rule = RecurrenceRule::new(calendar, Frequency::Monthly);
+            assert_eq!(RecurrenceRule::parse(&rule.to_rrule().unwrap()).unwrap(), rule);
+        }
+        assert!(RecurrenceRule::new(CalendarSystem::Japanese, Frequency::Yearly).to_rrule().is_err());
+
+        assert_eq!(RecurrenceRule::parse("RSCALE=DANGI;FREQ=MONTHLY;UNTIL=20250101T000000Z").unwrap().until, Some(ymd(2025, 1, 1)));
+        assert!(RecurrenceRule::parse("FREQ=YEARLY").is_err());
+        assert!(RecurrenceRule::parse("RSCALE=HEBREW;FREQ=YEARLY").is_err());
+        assert!(RecurrenceRule::parse("RSCALE=CHINESE;FREQ=YEARLY;BYMONTH=13").is_err());
+        assert!(RecurrenceRule::parse("RSCALE=CHINESE;FREQ=YEARLY;COUNT=2;UNTIL=20250101").is_err());
+    }
+
+    #[test]
+    fn test_interval_bounds() {
+        assert!(RecurrenceRule::parse("RSCALE=CHINESE;FREQ=YEARLY;INTERVAL=2147483648").is_err());
+        assert!(RecurrenceRule::parse("RSCALE=CHINESE;FREQ=YEARLY;INTERVAL=4294967295").is_err());
+        assert!(RecurrenceRule::parse("RSCALE=CHINESE;FREQ=YEARLY;INTERVAL=201").is_err());
+        assert!(RecurrenceRule::parse("RSCALE=CHINESE;FREQ=MONTHLY;INTERVAL=240").is_ok());
+
+        // Set directly, bypassing parse
+        let mut rule = RecurrenceRule::new(CalendarSystem::Chinese, Frequency::Yearly);
+        for interval in [2147483648, 4294967295, 2147483647] {
+            rule.interval = interval;
+            assert!(rule.occurrences(ymd(2024, 2, 10), 5).map_or(true, |dates| dates.len() == 1));
+        }
+    }
+
+    #[test]
+    fn test_yearly() {
+        // Mid-autumn, 15 of the eighth month
+        let rule = RecurrenceRule::parse("RSCALE=VIETNAMESE;FREQ=YEARLY;COUNT=3").unwrap();
+        assert_eq!(rule.occurrences(ymd(2023, 9, 29), 10).unwrap(), vec![ymd(2023, 9, 29), ymd(2024, 9, 17), ymd(2025, 10, 6)]);
+
+        let rule = RecurrenceRule::parse("RSCALE=VIETNAMESE;FREQ=YEARLY;INTERVAL=2;UNTIL=20280101").unwrap();
+        assert_eq!(rule.occurrences(ymd(2023, 9, 29), 10).unwrap(), vec![ymd(2023, 9, 29), ymd(2025, 10, 6), ymd(2027, 9, 15)]);
+    }
+
+    #[test]
+    fn test_leap_month_skip() {
+        // Quý Mão repeats the second month, the following years do not
+        let calendar = CalendarSystem::Vietnamese;
+        let dtstart = lunisolar(10, 2, 2023, true, &calendar);
+        let mut rule = RecurrenceRule::parse("RSCALE=VIETNAMESE;FREQ=YEARLY;BYMONTH=2L;COUNT=3").unwrap();
+        // The second month is next repeated in Nhâm Tuất
+        assert_eq!(rule.occurrences(dtstart, 10).unwrap(), vec![dtstart, lunisolar(10, 2, 2042, true, &calendar), ymd(2099, 3, 31)]);
+
+        rule.skip = Skip::Backward;
+        assert_eq!(rule.occurrences(dtstart, 10).unwrap(),
+                   vec![dtstart, lunisolar(10, 2, 2024, false, &calendar), lunisolar(10, 2, 2025, false, &calendar)]);
+
+        rule.skip = Skip::Forward;
+        assert_eq!(rule.occurrences(dtstart, 10).unwrap(),
+                   vec![dtstart, lunisolar(10, 3, 2024, false, &calendar), lunisolar(10, 3, 2025, false, &calendar)]);
+    }
+
+    #[test]
+    fn test_missing_30th() {
+        // The twelfth month of Quý Mão has 30 days, of Giáp Thìn 29
+        let mut rule = RecurrenceRule::parse("RSCALE=VIETNAMESE;FREQ=YEARLY;BYMONTH=12;BYMONTHDAY=30;COUNT=2").unwrap();
+        assert_eq!(rule.occurrences(ymd(2024, 2, 9), 10).unwrap(), vec![ymd(2024, 2, 9), ymd(2033, 1, 30)]);
+        rule.skip = Skip::Backward;
+        assert_eq!(rule.occurrences(ymd(2024, 2, 9), 10).unwrap(), vec![ymd(2024, 2, 9), ymd(2025, 1, 28)]);
+        rule.skip = Skip::Forward;
+        assert_eq!(rule.occurrences(ymd(2024, 2, 9), 10).unwrap(), vec![ymd(2024, 2, 9), ymd(2025, 1, 29)]);
+
+        let rule = RecurrenceRule::parse("RSCALE=VIETNAMESE;FREQ=YEARLY;BYMONTH=12;BYMONTHDAY=-1;COUNT=2").unwrap();
+        assert_eq!(rule.occurrences(ymd(2024, 2, 9), 10).unwrap(), vec![ymd(2024, 2, 9), ymd(2025, 1, 28)]);
+    }
+
+    #[test]
+    fn test_monthly() {
+        // Rằm and mùng một of every month, the leap second month of Quý Mão included
+        let calendar = CalendarSystem::Vietnamese;
+        let rule = RecurrenceRule::parse("RSCALE=VIETNAMESE;FREQ=MONTHLY;BYMONTHDAY=1,15;COUNT=6").unwrap();
+        assert_eq!(rule.occurrences(lunisolar(1, 2, 2023, false, &calendar), 10).unwrap(), vec![
+            lunisolar(1, 2, 2023, false, &calendar),
+            lunisolar(15, 2, 2023, false, &calendar),
+            lunisolar(1, 2, 2023, true, &calendar),
+            lunisolar(15, 2, 2023, true, &calendar),
+            lunisolar(1, 3, 2023, false, &calendar),
+            lunisolar(15, 3, 2023, false, &calendar),
+        ]);
+
+        let rule = RecurrenceRule::parse("RSCALE=CHINESE;FREQ=MONTHLY;INTERVAL=2").unwrap();
+        let calendar = CalendarSystem::Chinese;
+        assert_eq!(rule.occurrences(lunisolar(1, 1, 2024, false, &calendar), 3).unwrap(), vec![
+            lunisolar(1, 1, 2024, false, &calendar),
+            lunisolar(1, 3, 2024, false, &calendar),
+            lunisolar(1, 5, 2024, false, &calendar),
+        ]);
+    }
+}
@@ -0,0 +1,288 @@
+// JSON shape of the calendar types with the `serde` feature:
+//
+// NaiveLunisolarDateTime  {"hour":10,"minute":30,"day":15,"month":4,"leap":true,"year":2020}
+// LunarFormat             {"stem":"Giáp","branch":"Thìn"}
+// NaiveLunarDateTime      {"hour":<LunarFormat>,"day":<LunarFormat>,"month":<LunarFormat>,"year":<LunarFormat>}
+// LunarDateTimeObject     {"lunisolar":<NaiveLunisolarDateTime>,"lunar":<NaiveLunarDateTime>,"gregorian":"2020-06-05T10:30:00+07:00"}
+// Zodiac, Element, YinYang lowercase variant names such as "dragon", "wood", "yang"
+//
+// Stems and branches use their Vietnamese names so Tý and Tỵ stay distinct.
+// Deserializing checks what the types cannot: a pillar is one of the sixty pairs of matching parity, a
+// lunisolar date exists in the Vietnamese calendar, and the three views of a LunarDateTimeObject agree.
+// The `compact` modules are meant for `#[serde(with = "...")]` on fields of user types.
+use chrono::{DateTime, FixedOffset};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::Error;
+use super::lunar_datetime::*;
+use super::lunisolar_datetime::*;
+use super::calendar_system::*;
+use super::LunarDateTimeObject;
+
+impl Serialize for HeavenlyStem {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.vietnamese_name())
+    }
+}
+
+impl<'de> Deserialize<'de> for HeavenlyStem {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<HeavenlyStem, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        HeavenlyStem::from_vietnamese_name(&name).ok_or_else(|| D::Error::custom(format!("unknown heavenly stem {}", name)))
+    }
+}
+
+impl Serialize for EarthlyBranch {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.vietnamese_name())
+    }
+}
+
+impl<'de> Deserialize<'de> for EarthlyBranch {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<EarthlyBranch, D::Error> {
+        let name = String::deserialize(deserializer)?;
+        EarthlyBranch::from_vietnamese_name(&name).ok_or_else(|| D::Error::custom(format!("unknown earthly branch {}", name)))
+    }
+}
+
+fn checked_pillar(stem: HeavenlyStem, branch: EarthlyBranch) -> Result<LunarFormat, String> {
+    if stem.to_numeric() % 2 != branch.to_numeric() % 2 {
+        return Err(format!("{} {} is not a sexagenary pillar", stem.vietnamese_name(), branch.vietnamese_name()));
+    }
+    Ok(LunarFormat::new(stem, branch))
+}
+
+// Leap month and day 30 reckoned at UTC+7, the compact form and the default shape alike
+fn checked_lunisolar(datetime: NaiveLunisolarDateTime) -> Result<NaiveLunisolarDateTime, String> {
+    let year = i32::try_from(datetime.year_lunisolar).map_err(|_| format!("invalid lunisolar year {}", datetime.year_lunisolar))?;
+    let days_in_month = NaiveLunisolarDateTime::days_in_lunisolar_month(
+        datetime.month_lunisolar, year, datetime.leap_lunisolar, CalendarSystem::Vietnamese.time_zone())
+        .map_err(|error| error.to_string())?;
+    if !(1..=days_in_month).contains(&datetime.day_lunisolar) || datetime.hour_lunisolar > 23 || datetime.minute_lunisolar > 59 {
+        return Err(format!("invalid lunisolar date time {}", compact::lunisolar::to_string(&datetime)));
+    }
+    Ok(datetime)
+}
+
+#[derive(Deserialize)]
+struct LunarFormatFields {
+    stem: HeavenlyStem,
+    branch: EarthlyBranch,
+}
+
+impl<'de> Deserialize<'de> for LunarFormat {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<LunarFormat, D::Error> {
+        let fields = LunarFormatFields::deserialize(deserializer)?;
+        checked_pillar(fields.stem, fields.branch).map_err(D::Error::custom)
+    }
+}
+
+#[derive(Deserialize)]
+struct LunisolarFields {
+    hour: u32,
+    minute: u32,
+    day: u32,
+    month: u32,
+    leap: bool,
+    year: u32,
+}
+
+impl From<LunisolarFields> for NaiveLunisolarDateTime {
+    fn from(fields: LunisolarFields) -> NaiveLunisolarDateTime {
+        NaiveLunisolarDateTime {
+            hour_lunisolar: fields.hour,
+            minute_lunisolar: fields.minute,
+            day_lunisolar: fields.day,
+            month_lunisolar: fields.month,
+            leap_lunisolar: fields.leap,
+            year_lunisolar: fields.year,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for NaiveLunisolarDateTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<NaiveLunisolarDateTime, D::Error> {
+        checked_lunisolar(LunisolarFields::deserialize(deserializer)?.into()).map_err(D::Error::custom)
+    }
+}
+
+// The lunisolar date is only checked against the gregorian one, which also covers meridians other than UTC+7
+#[derive(Deserialize)]
+struct LunarDateTimeObjectFields {
+    lunisolar: LunisolarFields,
+    lunar: NaiveLunarDateTime,
+    gregorian: DateTime<FixedOffset>,
+}
+
+impl<'de> Deserialize<'de> for LunarDateTimeObject {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<LunarDateTimeObject, D::Error> {
+        let fields = LunarDateTimeObjectFields::deserialize(deserializer)?;
+        let gregorian_datetime = fields.gregorian;
+        let lunisolar_datetime = NaiveLunisolarDateTime::new_from_datetime(gregorian_datetime).map_err(D::Error::custom)?;
+        let lunar_datetime = NaiveLunarDateTime::new_from_datetime(gregorian_datetime).map_err(D::Error::custom)?;
+        if NaiveLunisolarDateTime::from(fields.lunisolar) != lunisolar_datetime {
+            return Err(D::Error::custom(format!("lunisolar date does not match {}", gregorian_datetime)));
+        }
+        if fields.lunar != lunar_datetime {
+            return Err(D::Error::custom(format!("lunar pillars do not match {}", gregorian_datetime)));
+        }
+        Ok(LunarDateTimeObject { lunisolar_datetime, lunar_datetime, gregorian_datetime })
+    }
+}
+
+pub mod compact {
+    // NaiveLunisolarDateTime as "2020-04L-15 10:30", the L marking a leap month.
+    // Only dates of the Vietnamese calendar are read back: the leap month and day 30 have to exist at UTC+7.
+    pub mod lunisolar {
+        use super::super::*;
+
+        pub fn to_string(datetime: &NaiveLunisolarDateTime) -> String {
+            format!("{:04}-{:02}{}-{:02} {:02}:{:02}",
+                    datetime.year_lunisolar,
+                    datetime.month_lunisolar,
+                    if datetime.leap_lunisolar { "L" } else { "" },
+                    datetime.day_lunisolar,
+                    datetime.hour_lunisolar,
+                    datetime.minute_lunisolar)
+        }
+
+        pub fn from_str(value: &str) -> Option<NaiveLunisolarDateTime> {
+            let (date, time) = value.trim().split_once(' ')?;
+            let mut date_parts = date.split('-');
+            let year = date_parts.next()?.parse().ok()?;
+            let month_part = date_parts.next()?;
+            let day = date_parts.next()?.parse().ok()?;
+            if date_parts.next().is_some() {
+                return None;
+            }
+            let (month, leap) = match month_part.strip_suffix('L') {
+                Some(month) => (month.parse().ok()?, true),
+                None => (month_part.parse().ok()?, false),
+            };
+            let (hour, minute) = time.split_once(':')?;
+            let (hour, minute): (u32, u32) = (hour.parse().ok()?, minute.parse().ok()?);
+            super::super::checked_lunisolar(NaiveLunisolarDateTime {
+                hour_lunisolar: hour,
+                minute_lunisolar: minute,
+                day_lunisolar: day,
+                month_lunisolar: month,
+                leap_lunisolar: leap,
+                year_lunisolar: year,
+            }).ok()
+        }
+
+        pub fn serialize<S: Serializer>(datetime: &NaiveLunisolarDateTime, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&to_string(datetime))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<NaiveLunisolarDateTime, D::Error> {
+            let value = String::deserialize(deserializer)?;
+            from_str(&value).ok_or_else(|| D::Error::custom(format!("invalid lunisolar date time {}", value)))
+        }
+    }
+
+    // LunarFormat as "Giáp Thìn", one of the sixty pairs whose stem and branch have the same parity
+    pub mod pillar {
+        use super::super::*;
+
+        pub fn to_string(pillar: &LunarFormat) -> String {
+            format!("{} {}", pillar.stem.vietnamese_name(), pillar.branch.vietnamese_name())
+        }
+
+        pub fn from_str(value: &str) -> Option<LunarFormat> {
+            let (stem, branch) = value.trim().split_once(' ')?;
+            super::super::checked_pillar(HeavenlyStem::from_vietnamese_name(stem)?, EarthlyBranch::from_vietnamese_name(branch)?).ok()
+        }
+
+        pub fn serialize<S: Serializer>(pillar: &LunarFormat, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&to_string(pillar))
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<LunarFormat, D::Error> {
+            let value = String::deserialize(deserializer)?;
+            from_str(&value).ok_or_else(|| D::Error::custom(format!("invalid sexagenary pillar {}", value)))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+    use super::*;
+    use super::super::*;
+
+    #[test]
+    fn test_json_shape() {
+        // 15 of the leap fourth month of Canh Tý
+        let object = LunarDateTimeObject::new_from_gregorian_datetime(5, 6, 2020, 10, 30, 7).unwrap();
+        let value = serde_json::to_value(object).unwrap();
+        assert_eq!(value["lunisolar"], json!({"hour": 10, "minute": 30, "day": 14, "month": 4, "leap": true, "year": 2020}));
+        assert_eq!(value["lunar"]["year"], json!({"stem": "Canh", "branch": "Tý"}));
+        assert_eq!(value["gregorian"], json!("2020-06-05T10:30:00+07:00"));
+
+        let back: LunarDateTimeObject = serde_json::from_value(value).unwrap();
+        assert_eq!(back.gregorian_datetime, object.gregorian_datetime);
+        assert_eq!(back.lunar_datetime.date_lunar, object.lunar_datetime.date_lunar);
+        assert!(back.lunisolar_datetime.leap_lunisolar);
+
+        assert_eq!(serde_json::to_string(&Zodiac::Dragon).unwrap(), "\"dragon\"");
+        assert_eq!(serde_json::from_str::<EarthlyBranch>("\"Tỵ\"").unwrap(), EarthlyBranch::EB6);
+        assert!(serde_json::from_str::<HeavenlyStem>("\"Foo\"").is_err());
+    }
+
+    #[derive(Serialize, Deserialize)]
+    struct Record {
+        #[serde(with = "compact::lunisolar")]
+        date: NaiveLunisolarDateTime,
+        #[serde(with = "compact::pillar")]
+        year: LunarFormat,
+    }
+
+    #[test]
+    fn test_compact() {
+        let json = r#"{"date":"2020-04L-14 10:30","year":"Canh Tý"}"#;
+        let record: Record = serde_json::from_str(json).unwrap();
+        assert_eq!(record.date.month_lunisolar, 4);
+        assert!(record.date.leap_lunisolar);
+        assert_eq!(record.year, LunarFormat::new(HeavenlyStem::HS7, EarthlyBranch::EB1));
+        assert_eq!(serde_json::to_string(&record).unwrap(), json);
+
+        assert!(compact::lunisolar::from_str("2020-13-01 00:00").is_none());
+        assert!(compact::pillar::from_str("Canh").is_none());
+        assert!(compact::pillar::from_str("Giáp Sửu").is_none());
+        assert!(compact::lunisolar::from_str("2024-02L-01 00:00").is_none());
+        assert!(compact::lunisolar::from_str("2024-12-30 00:00").is_none());
+        assert!(compact::lunisolar::from_str("2023-12-30 00:00").is_some());
+        assert!(compact::lunisolar::from_str("3000-01-01 00:00").is_none());
+        assert!(serde_json::from_str::<Record>(r#"{"date":"2020-04L-14 10:30","year":"Giáp Sửu"}"#).is_err());
+        assert!(serde_json::from_str::<Record>(r#"{"date":"2024-02L-01 10:30","year":"Canh Tý"}"#).is_err());
+    }
+
+    #[test]
+    fn test_reject_default_shape() {
+        assert!(serde_json::from_value::<LunarFormat>(json!({"stem": "Canh", "branch": "Tý"})).is_ok());
+        assert!(serde_json::from_value::<LunarFormat>(json!({"stem": "Giáp", "branch": "Sửu"})).is_err());
+
+        let date = |day, month, leap| json!({"hour": 0, "minute": 0, "day": day, "month": month, "leap": leap, "year": 2024});
+        assert!(serde_json::from_value::<NaiveLunisolarDateTime>(date(1, 1, false)).is_ok());
+        assert!(serde_json::from_value::<NaiveLunisolarDateTime>(date(1, 13, false)).is_err());
+        assert!(serde_json::from_value::<NaiveLunisolarDateTime>(date(1, 0, false)).is_err());
+        assert!(serde_json::from_value::<NaiveLunisolarDateTime>(date(1, 2, true)).is_err());
+        assert!(serde_json::from_value::<NaiveLunisolarDateTime>(date(31, 1, false)).is_err());
+        assert!(serde_json::from_value::<NaiveLunisolarDateTime>(
+            json!({"hour": 24, "minute": 0, "day": 1, "month": 1, "leap": false, "year": 2024})).is_err());
+
+        let object = LunarDateTimeObject::new_from_gregorian_datetime(5, 6, 2020, 10, 30, 7).unwrap();
+        let mut value = serde_json::to_value(object).unwrap();
+        value["gregorian"] = json!("2020-06-06T10:30:00+07:00");
+        assert!(serde_json::from_value::<LunarDateTimeObject>(value).is_err());
+
+        let mut value = serde_json::to_value(object).unwrap();
+        value["lunar"]["year"] = json!({"stem": "Tân", "branch": "Sửu"});
+        assert!(serde_json::from_value::<LunarDateTimeObject>(value).is_err());
+
+        // Valid at its own meridian even though UTC+7 is already on the next day
+        let object = LunarDateTimeObject::new_from_gregorian_datetime(5, 6, 2020, 23, 30, -5).unwrap();
+        let back: LunarDateTimeObject = serde_json::from_value(serde_json::to_value(object).unwrap()).unwrap();
+        assert_eq!(back.lunisolar_datetime, object.lunisolar_datetime);
+    }
+}
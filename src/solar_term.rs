@@ -0,0 +1,183 @@
+use anyhow::{bail, Result};
+use chrono::{Datelike, DateTime, FixedOffset, NaiveDate};
+use super::lunar_datetime::*;
+use super::lunisolar_datetime::*;
+
+// Tiết khí, every 15 degrees of sun longitude starting from Xuân phân at 0 degree
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum SolarTerm {
+    XuanPhan,
+    ThanhMinh,
+    CocVu,
+    LapHa,
+    TieuMan,
+    MangChung,
+    HaChi,
+    TieuThu,
+    DaiThu,
+    LapThu,
+    XuThu,
+    BachLo,
+    ThuPhan,
+    HanLo,
+    SuongGiang,
+    LapDong,
+    TieuTuyet,
+    DaiTuyet,
+    DongChi,
+    TieuHan,
+    DaiHan,
+    LapXuan,
+    VuThuy,
+    KinhTrap,
+}
+
+impl SolarTerm {
+    pub fn from_numeric(num: u32) -> SolarTerm {
+        match num % 24 {
+            0 => SolarTerm::XuanPhan,
+            1 => SolarTerm::ThanhMinh,
+            2 => SolarTerm::CocVu,
+            3 => SolarTerm::LapHa,
+            4 => SolarTerm::TieuMan,
+            5 => SolarTerm::MangChung,
+            6 => SolarTerm::HaChi,
+            7 => SolarTerm::TieuThu,
+            8 => SolarTerm::DaiThu,
+            9 => SolarTerm::LapThu,
+            10 => SolarTerm::XuThu,
+            11 => SolarTerm::BachLo,
+            12 => SolarTerm::ThuPhan,
+            13 => SolarTerm::HanLo,
+            14 => SolarTerm::SuongGiang,
+            15 => SolarTerm::LapDong,
+            16 => SolarTerm::TieuTuyet,
+            17 => SolarTerm::DaiTuyet,
+            18 => SolarTerm::DongChi,
+            19 => SolarTerm::TieuHan,
+            20 => SolarTerm::DaiHan,
+            21 => SolarTerm::LapXuan,
+            22 => SolarTerm::VuThuy,
+            _ => SolarTerm::KinhTrap,
+        }
+    }
+
+    pub fn to_numeric(&self) -> u32 {
+        match self {
+            SolarTerm::XuanPhan => 0,
+            SolarTerm::ThanhMinh => 1,
+            SolarTerm::CocVu => 2,
+            SolarTerm::LapHa => 3,
+            SolarTerm::TieuMan => 4,
+            SolarTerm::MangChung => 5,
+            SolarTerm::HaChi => 6,
+            SolarTerm::TieuThu => 7,
+            SolarTerm::DaiThu => 8,
+            SolarTerm::LapThu => 9,
+            SolarTerm::XuThu => 10,
+            SolarTerm::BachLo => 11,
+            SolarTerm::ThuPhan => 12,
+            SolarTerm::HanLo => 13,
+            SolarTerm::SuongGiang => 14,
+            SolarTerm::LapDong => 15,
+            SolarTerm::TieuTuyet => 16,
+            SolarTerm::DaiTuyet => 17,
+            SolarTerm::DongChi => 18,
+            SolarTerm::TieuHan => 19,
+            SolarTerm::DaiHan => 20,
+            SolarTerm::LapXuan => 21,
+            SolarTerm::VuThuy => 22,
+            SolarTerm::KinhTrap => 23,
+        }
+    }
+
+    // Sun longitude at which the term begins, in degree
+    pub fn longitude(&self) -> u32 {
+        self.to_numeric() * 15
+    }
+
+    // A day belongs to the term the sun has reached by the end of that day (local midnight)
+    pub fn from_gregorian_ymd(day: u32, month: u32, year: i32, time_zone: f64) -> SolarTerm {
+        let jd = NaiveLunisolarDateTime::jd_from_date(day, month, year) + 1;
+        let sun_long = NaiveLunisolarDateTime::get_sun_longitude(jd as f64, time_zone);
+        SolarTerm::from_numeric((sun_long * 2.0) as u32)
+    }
+
+    pub fn from_datetime(datetime: DateTime<FixedOffset>) -> SolarTerm {
+        let time_zone = datetime.timezone().local_minus_utc() as f64 / 3600_f64;
+        SolarTerm::from_gregorian_ymd(datetime.day(), datetime.month(), datetime.year(), time_zone)
+    }
+
+    // First day of every term beginning in the gregorian year, in calendar order
+    pub fn dates_in_year(year: i32, time_zone: f64) -> Result<Vec<(SolarTerm, NaiveDate)>> {
+        if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+            bail!("year should be between {} and {}", MIN_YEAR, MAX_YEAR);
+        }
+        let first_date = NaiveDate::from_ymd_opt(year, 1, 1).unwrap();
+        let mut previous = first_date.pred_opt()
+            .map(|date| SolarTerm::from_gregorian_ymd(date.day(), date.month(), date.year(), time_zone));
+        let mut result = Vec::new();
+        for date in first_date.iter_days().take_while(|date| date.year() == year) {
+            let term = SolarTerm::from_gregorian_ymd(date.day(), date.month(), date.year(), time_zone);
+            if previous.as_ref() != Some(&term) {
+                result.push((term, date));
+            }
+            previous = Some(term);
+        }
+        Ok(result)
+    }
+
+    pub fn start_date_in_year(&self, year: i32, time_zone: f64) -> Result<Option<NaiveDate>> {
+        Ok(SolarTerm::dates_in_year(year, time_zone)?.into_iter()
+            .find(|(term, _)| term == self)
+            .map(|(_, date)| date))
+    }
+
+    // Tiết (odd) terms open a solar month, Khí (even) terms sit in the middle of it
+    pub fn is_month_start(&self) -> bool {
+        self.to_numeric() % 2 == 1
+    }
+
+    // Solar month opened by Lập Xuân is Dần, by Kinh Trập is Mão and so on
+    pub fn solar_month_branch(&self) -> EarthlyBranch {
+        EarthlyBranch::from_numeric((self.to_numeric() + 1) % 24 / 2 + 3)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use super::*;
+
+    #[rstest]
+    #[case(2024, 2, 3, SolarTerm::DaiHan)]
+    #[case(2024, 2, 4, SolarTerm::LapXuan)]
+    #[case(2024, 3, 20, SolarTerm::XuanPhan)]
+    #[case(2024, 4, 4, SolarTerm::ThanhMinh)]
+    #[case(2024, 6, 21, SolarTerm::HaChi)]
+    #[case(2024, 12, 21, SolarTerm::DongChi)]
+    fn test_solar_term(#[case] yyyy: i32, #[case] mm: u32, #[case] dd: u32, #[case] expected: SolarTerm) {
+        assert_eq!(SolarTerm::from_gregorian_ymd(dd, mm, yyyy, 7.0), expected);
+    }
+
+    #[test]
+    fn test_dates_in_year() {
+        let dates = SolarTerm::dates_in_year(2024, 7.0).unwrap();
+        assert_eq!(dates.len(), 24);
+        assert_eq!(dates[0], (SolarTerm::TieuHan, NaiveDate::from_ymd_opt(2024, 1, 6).unwrap()));
+        assert_eq!(SolarTerm::ThanhMinh.start_date_in_year(2024, 7.0).unwrap(), NaiveDate::from_ymd_opt(2024, 4, 4));
+        assert!(SolarTerm::dates_in_year(1, 7.0).is_err());
+        assert!(SolarTerm::dates_in_year(300000, 7.0).is_err());
+        assert!(SolarTerm::LapXuan.start_date_in_year(2101, 7.0).is_err());
+    }
+
+    #[test]
+    fn test_solar_month_branch() {
+        assert_eq!(SolarTerm::LapXuan.solar_month_branch(), EarthlyBranch::EB_TIGER);
+        assert_eq!(SolarTerm::VuThuy.solar_month_branch(), EarthlyBranch::EB_TIGER);
+        assert_eq!(SolarTerm::KinhTrap.solar_month_branch(), EarthlyBranch::EB_CAT);
+        assert_eq!(SolarTerm::XuanPhan.solar_month_branch(), EarthlyBranch::EB_CAT);
+        assert_eq!(SolarTerm::DongChi.solar_month_branch(), EarthlyBranch::EB_RAT);
+        assert_eq!(SolarTerm::DaiHan.solar_month_branch(), EarthlyBranch::EB_BUFFALO);
+    }
+}
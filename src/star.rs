@@ -0,0 +1,195 @@
+use anyhow::{Result, bail, anyhow};
+use super::lunar_datetime::*;
+use super::zodiac_and_element::*;
+use super::solar_term::*;
+use super::LunarDateTimeObject;
+
+const DEFAULT_STAR_TABLE: &str = include_str!("../data/stars.txt");
+
+// Which month a rule is keyed on: the lunisolar month number or the solar-term month (Dần = 1)
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum MonthBasis {
+    Lunar,
+    Solar,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum StarTarget {
+    Stem(HeavenlyStem),
+    Branch(EarthlyBranch),
+    Pillar(LunarFormat),
+}
+
+impl StarTarget {
+    pub fn parse(text: &str) -> Result<StarTarget> {
+        let words: Vec<&str> = text.split_whitespace().collect();
+        match words.as_slice() {
+            [word] => {
+                if let Some(stem) = HeavenlyStem::from_vietnamese_name(word) {
+                    Ok(StarTarget::Stem(stem))
+                } else if let Some(branch) = EarthlyBranch::from_vietnamese_name(word) {
+                    Ok(StarTarget::Branch(branch))
+                } else {
+                    bail!("unknown stem or branch '{}'", word)
+                }
+            }
+            [stem, branch] => {
+                let stem = HeavenlyStem::from_vietnamese_name(stem).ok_or_else(|| anyhow!("unknown stem '{}'", stem))?;
+                let branch = EarthlyBranch::from_vietnamese_name(branch).ok_or_else(|| anyhow!("unknown branch '{}'", branch))?;
+                Ok(StarTarget::Pillar(LunarFormat::new(stem, branch)))
+            }
+            _ => bail!("invalid day '{}'", text),
+        }
+    }
+
+    pub fn matches(&self, day: &LunarFormat) -> bool {
+        match self {
+            StarTarget::Stem(stem) => { *stem == day.stem }
+            StarTarget::Branch(branch) => { *branch == day.branch }
+            StarTarget::Pillar(pillar) => { pillar == day }
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StarRule {
+    pub name: String,
+    pub fortune: Fortune,
+    pub basis: MonthBasis,
+    // One entry per month, each listing the days on which the star is present
+    pub targets: Vec<Vec<StarTarget>>,
+}
+
+impl StarRule {
+    // Line format: name | good or bad | lunar or solar | month 1; month 2; ...; month 12
+    pub fn parse(line: &str) -> Result<StarRule> {
+        let fields: Vec<&str> = line.split('|').map(|f| f.trim()).collect();
+        if fields.len() != 4 {
+            bail!("expected 4 fields separated by '|' in '{}'", line);
+        }
+        let fortune = match fields[1] {
+            "good" => Fortune::Good,
+            "bad" => Fortune::Bad,
+            other => bail!("unknown fortune '{}' for star {}", other, fields[0]),
+        };
+        let basis = match fields[2] {
+            "lunar" => MonthBasis::Lunar,
+            "solar" => MonthBasis::Solar,
+            other => bail!("unknown month basis '{}' for star {}", other, fields[0]),
+        };
+        let targets = fields[3].split(';')
+            .map(|month| month.split('/').map(StarTarget::parse).collect::<Result<Vec<StarTarget>>>())
+            .collect::<Result<Vec<Vec<StarTarget>>>>()?;
+        if targets.len() != 12 {
+            bail!("star {} should list 12 months, found {}", fields[0], targets.len());
+        }
+        Ok(StarRule {
+            name: fields[0].to_string(),
+            fortune,
+            basis,
+            targets,
+        })
+    }
+
+    pub fn is_present(&self, date: &LunarDateTimeObject) -> bool {
+        let month = match self.basis {
+            MonthBasis::Lunar => date.lunisolar_datetime.month_lunisolar,
+            MonthBasis::Solar => {
+                let branch = SolarTerm::from_datetime(date.gregorian_datetime).solar_month_branch();
+                (branch.to_numeric() + 10) % 12 + 1
+            }
+        };
+        // A hand-built date with month 0 or above 12 carries no star
+        match (month as usize).checked_sub(1).and_then(|index| self.targets.get(index)) {
+            Some(targets) => targets.iter().any(|target| target.matches(&date.lunar_datetime.date_lunar)),
+            None => false,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct StarTable {
+    pub rules: Vec<StarRule>,
+}
+
+impl StarTable {
+    // Blank lines and lines starting with '#' are ignored
+    pub fn parse(text: &str) -> Result<StarTable> {
+        let rules = text.lines()
+            .map(|line| line.trim())
+            .filter(|line| !line.is_empty() && !line.starts_with('#'))
+            .map(StarRule::parse)
+            .collect::<Result<Vec<StarRule>>>()?;
+        Ok(StarTable { rules })
+    }
+
+    pub fn evaluate(&self, date: &LunarDateTimeObject) -> Vec<&StarRule> {
+        self.rules.iter().filter(|rule| rule.is_present(date)).collect()
+    }
+
+    pub fn good_stars(&self, date: &LunarDateTimeObject) -> Vec<&StarRule> {
+        self.evaluate(date).into_iter().filter(|rule| rule.fortune == Fortune::Good).collect()
+    }
+
+    pub fn bad_stars(&self, date: &LunarDateTimeObject) -> Vec<&StarRule> {
+        self.evaluate(date).into_iter().filter(|rule| rule.fortune == Fortune::Bad).collect()
+    }
+}
+
+impl Default for StarTable {
+    fn default() -> Self {
+        StarTable::parse(DEFAULT_STAR_TABLE).expect("embedded star table should be valid")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn names(rules: Vec<&StarRule>) -> Vec<&str> {
+        rules.iter().map(|rule| rule.name.as_str()).collect()
+    }
+
+    #[test]
+    fn test_default_table() {
+        let table = StarTable::default();
+        assert_eq!(table.rules.len(), 15);
+
+        // Tết Giáp Thìn: Giáp Thìn day of lunar month 1
+        let date = LunarDateTimeObject::new_from_gregorian_datetime(10, 2, 2024, 12, 0, 7).unwrap();
+        assert_eq!(names(table.good_stars(&date)), vec!["Thiên Quý"]);
+        assert!(table.bad_stars(&date).is_empty());
+
+        // 2024-02-16 is a Canh Tuất day, Thiên Hỷ of month 1 and Thụ Tử
+        let date = LunarDateTimeObject::new_from_gregorian_datetime(16, 2, 2024, 12, 0, 7).unwrap();
+        assert_eq!(names(table.good_stars(&date)), vec!["Thiên Hỷ"]);
+        assert_eq!(names(table.bad_stars(&date)), vec!["Thụ Tử"]);
+    }
+
+    #[test]
+    fn test_custom_table() {
+        let table = StarTable::parse("
+            # one pillar for every month
+            Custom | good | solar | Giáp Thìn; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý
+        ").unwrap();
+        let date = LunarDateTimeObject::new_from_gregorian_datetime(10, 2, 2024, 12, 0, 7).unwrap();
+        assert_eq!(names(table.evaluate(&date)), vec!["Custom"]);
+    }
+
+    #[test]
+    fn test_invalid_month() {
+        let table = StarTable::parse("Custom | good | lunar | Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý").unwrap();
+        let mut date = LunarDateTimeObject::new_from_gregorian_datetime(10, 2, 2024, 12, 0, 7).unwrap();
+        date.lunisolar_datetime.month_lunisolar = 0;
+        assert!(table.evaluate(&date).is_empty());
+        date.lunisolar_datetime.month_lunisolar = 13;
+        assert!(table.evaluate(&date).is_empty());
+    }
+
+    #[test]
+    fn test_invalid_table() {
+        assert!(StarTable::parse("Short | good | lunar | Tý; Sửu").is_err());
+        assert!(StarTable::parse("Unknown | good | lunar | Foo; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý").is_err());
+        assert!(StarTable::parse("Basis | good | weekly | Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý; Tý").is_err());
+    }
+}
@@ -0,0 +1,119 @@
+use super::lunar_datetime::*;
+use super::LunarDateTimeObject;
+
+const TAM_NUONG_DAYS: [u32; 6] = [3, 7, 13, 18, 22, 27];
+const NGUYET_KY_DAYS: [u32; 3] = [5, 14, 23];
+
+// (lunar month, lunar day) of the 13 Dương Công kỵ nhật
+const DUONG_CONG_DAYS: [(u32, u32); 13] = [
+    (1, 13), (2, 11), (3, 9), (4, 7), (5, 5), (6, 3), (7, 8),
+    (7, 29), (8, 27), (9, 25), (10, 23), (11, 21), (12, 19),
+];
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TabooDay {
+    TamNuong,
+    NguyetKy,
+    SatChu,
+    ThoTu,
+    DuongCongKyNhat,
+}
+
+impl TabooDay {
+    // Sát chủ day branch for each lunar month, starting from month 1
+    fn sat_chu_branch(month: u32) -> EarthlyBranch {
+        match month {
+            1 => EarthlyBranch::EB_SNAKE,
+            2 => EarthlyBranch::EB_RAT,
+            3 => EarthlyBranch::EB_GOAT,
+            4 => EarthlyBranch::EB_CAT,
+            5 => EarthlyBranch::EB_MONKEY,
+            6 => EarthlyBranch::EB_DOG,
+            7 => EarthlyBranch::EB_PIG,
+            8 => EarthlyBranch::EB_BUFFALO,
+            9 => EarthlyBranch::EB_HORSE,
+            10 => EarthlyBranch::EB_CHICKEN,
+            11 => EarthlyBranch::EB_TIGER,
+            _ => EarthlyBranch::EB_DRAGON,
+        }
+    }
+
+    // Thọ tử day pillar for each lunar month, starting from month 1
+    fn tho_tu_pillar(month: u32) -> LunarFormat {
+        match month {
+            1 => LunarFormat::new(HeavenlyStem::HS_BINH, EarthlyBranch::EB_DOG),
+            2 => LunarFormat::new(HeavenlyStem::HS_NHAM, EarthlyBranch::EB_DRAGON),
+            3 => LunarFormat::new(HeavenlyStem::HS_TAN, EarthlyBranch::EB_PIG),
+            4 => LunarFormat::new(HeavenlyStem::HS_DINH, EarthlyBranch::EB_SNAKE),
+            5 => LunarFormat::new(HeavenlyStem::HS_MAU, EarthlyBranch::EB_RAT),
+            6 => LunarFormat::new(HeavenlyStem::HS_BINH, EarthlyBranch::EB_HORSE),
+            7 => LunarFormat::new(HeavenlyStem::HS_AT, EarthlyBranch::EB_BUFFALO),
+            8 => LunarFormat::new(HeavenlyStem::HS_QUY, EarthlyBranch::EB_GOAT),
+            9 => LunarFormat::new(HeavenlyStem::HS_GIAP, EarthlyBranch::EB_TIGER),
+            10 => LunarFormat::new(HeavenlyStem::HS_MAU, EarthlyBranch::EB_MONKEY),
+            11 => LunarFormat::new(HeavenlyStem::HS_TAN, EarthlyBranch::EB_CAT),
+            _ => LunarFormat::new(HeavenlyStem::HS_TAN, EarthlyBranch::EB_CHICKEN),
+        }
+    }
+}
+
+// Every taboo falling on the date, in the order of TabooDay
+pub fn taboos(date: &LunarDateTimeObject) -> Vec<TabooDay> {
+    let day = date.lunisolar_datetime.day_lunisolar;
+    let month = date.lunisolar_datetime.month_lunisolar;
+    let pillar = &date.lunar_datetime.date_lunar;
+
+    let mut result = Vec::new();
+    if TAM_NUONG_DAYS.contains(&day) {
+        result.push(TabooDay::TamNuong);
+    }
+    if NGUYET_KY_DAYS.contains(&day) {
+        result.push(TabooDay::NguyetKy);
+    }
+    if pillar.branch == TabooDay::sat_chu_branch(month) {
+        result.push(TabooDay::SatChu);
+    }
+    if *pillar == TabooDay::tho_tu_pillar(month) {
+        result.push(TabooDay::ThoTu);
+    }
+    if DUONG_CONG_DAYS.contains(&(month, day)) {
+        result.push(TabooDay::DuongCongKyNhat);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use super::*;
+
+    #[rstest]
+    // Lunar 5/5 Giáp Thìn
+    #[case(2024, 6, 10, vec![TabooDay::NguyetKy, TabooDay::DuongCongKyNhat])]
+    // Lunar 3/1 Giáp Thìn
+    #[case(2024, 2, 12, vec![TabooDay::TamNuong])]
+    // Lunar 7/1 Giáp Thìn, a Canh Tuất day
+    #[case(2024, 2, 16, vec![TabooDay::TamNuong])]
+    // Lunar 13/1 Giáp Thìn, a Bính Thìn day
+    #[case(2024, 2, 22, vec![TabooDay::TamNuong, TabooDay::DuongCongKyNhat])]
+    // Lunar 1/1 Giáp Thìn
+    #[case(2024, 2, 10, vec![])]
+    fn test_taboos(#[case] yyyy: i32, #[case] mm: u32, #[case] dd: u32, #[case] expected: Vec<TabooDay>) {
+        let date = LunarDateTimeObject::new_from_gregorian_datetime(dd, mm, yyyy, 12, 0, 7).unwrap();
+        assert_eq!(taboos(&date), expected);
+    }
+
+    #[test]
+    fn test_sat_chu_and_tho_tu() {
+        // 2024-02-14 is lunar 5/1, a Mậu Thân day
+        let date = LunarDateTimeObject::new_from_gregorian_datetime(14, 2, 2024, 12, 0, 7).unwrap();
+        assert_eq!(taboos(&date), vec![TabooDay::NguyetKy]);
+        // 2024-02-11 is lunar 2/1, an Ất Tỵ day
+        let date = LunarDateTimeObject::new_from_gregorian_datetime(11, 2, 2024, 12, 0, 7).unwrap();
+        assert_eq!(date.lunar_datetime.date_lunar.branch, EarthlyBranch::EB_SNAKE);
+        assert_eq!(taboos(&date), vec![TabooDay::SatChu]);
+        // 2024-03-29 is lunar 20/2, a Nhâm Thìn day
+        let date = LunarDateTimeObject::new_from_gregorian_datetime(29, 3, 2024, 12, 0, 7).unwrap();
+        assert_eq!(taboos(&date), vec![TabooDay::ThoTu]);
+    }
+}
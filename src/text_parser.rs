@@ -0,0 +1,390 @@
+use anyhow::{Result, anyhow, bail};
+use super::lunar_datetime::*;
+use super::lunisolar_datetime::*;
+use super::localization::*;
+
+const LOCALES: [Locale; 6] = [Locale::Vi, Locale::ZhHans, Locale::ZhHant, Locale::Ko, Locale::Ja, Locale::En];
+const ROMANIZATIONS: [Romanization; 2] = [Romanization::Pinyin, Romanization::HanViet];
+
+// ASCII spellings people type for names whose folded form is not enough
+const STEM_ALIASES: [(&str, u32); 3] = [("gap", 0), ("ki", 5), ("qui", 9)];
+const BRANCH_ALIASES: [(&str, u32); 3] = [("ti", 0), ("ti", 5), ("meo", 3)];
+
+const CALENDAR_PREFIXES: [&str; 6] = ["农历", "農曆", "阴历", "陰曆", "旧历", "舊曆"];
+
+// Groups of letters folding to the first one, covering Vietnamese diacritics and pinyin tone marks
+const FOLDED_LETTERS: [&str; 7] = [
+    "aàáảãạăằắẳẵặâầấẩẫậāǎ",
+    "eèéẻẽẹêềếểễệēě",
+    "iìíỉĩịīǐ",
+    "oòóỏõọôồốổỗộơờớởỡợōǒ",
+    "uùúủũụưừứửữựūǔǖǘǚǜü",
+    "yỳýỷỹỵ",
+    "dđ",
+];
+
+fn fold_char(c: char) -> char {
+    FOLDED_LETTERS.iter()
+        .find(|letters| letters.contains(c))
+        .and_then(|letters| letters.chars().next())
+        .unwrap_or(c)
+}
+
+// Lowercase without diacritics, so "Giáp Tý", "giap ty" and "GIÁP TÝ" compare equal
+pub(crate) fn fold(text: &str) -> String {
+    text.to_lowercase().chars().map(fold_char).collect()
+}
+
+fn is_separator(c: char) -> bool {
+    c.is_whitespace() || matches!(c, '-' | '_' | '·' | '.' | ',' | '/' | '(' | ')')
+}
+
+fn is_cjk(c: char) -> bool {
+    ('\u{4E00}'..='\u{9FFF}').contains(&c) || c == '〇'
+}
+
+fn stem_spellings(stem: &HeavenlyStem) -> Vec<String> {
+    let names = LOCALES.iter().map(|locale| stem.localized_name(locale))
+        .chain(ROMANIZATIONS.iter().map(|romanization| stem.romanized_name(romanization)));
+    let aliases = STEM_ALIASES.iter().filter(|(_, num)| *num == stem.to_numeric()).map(|(alias, _)| *alias);
+    names.chain(aliases).map(|name| fold(name).chars().filter(|c| !is_separator(*c)).collect()).collect()
+}
+
+fn branch_spellings(branch: &EarthlyBranch) -> Vec<String> {
+    let names = LOCALES.iter().map(|locale| branch.localized_name(locale))
+        .chain(ROMANIZATIONS.iter().map(|romanization| branch.romanized_name(romanization)));
+    let aliases = BRANCH_ALIASES.iter().filter(|(_, num)| *num == branch.to_numeric()).map(|(alias, _)| *alias);
+    names.chain(aliases).map(|name| fold(name).chars().filter(|c| !is_separator(*c)).collect()).collect()
+}
+
+// Only the 60 pairs of matching parity exist, which also tells Tý from Tỵ once folded
+fn pillar_from_compact(text: &str) -> Option<LunarFormat> {
+    for stem in HeavenlyStem::ALL.into_iter() {
+        for stem_spelling in stem_spellings(&stem) {
+            let Some(rest) = text.strip_prefix(stem_spelling.as_str()) else { continue };
+            let found = EarthlyBranch::ALL.into_iter()
+                .filter(|branch| branch.to_numeric() % 2 == stem.to_numeric() % 2)
+                .find(|branch| branch_spellings(branch).iter().any(|spelling| spelling == rest));
+            if let Some(branch) = found {
+                return Some(LunarFormat::new(stem, branch));
+            }
+        }
+    }
+    None
+}
+
+fn cjk_digit(c: char) -> Option<u32> {
+    "〇一二三四五六七八九".chars().position(|digit| digit == c).map(|position| position as u32)
+        .or_else(|| if c == '零' { Some(0) } else { c.to_digit(10) })
+}
+
+// "二〇二三" or "2023" digit by digit, "十五", "二十三", "廿三", "卅" positionally
+pub(crate) fn parse_cjk_number(text: &str) -> Option<u32> {
+    if text.is_empty() {
+        return None;
+    }
+    if let Some(digits) = text.chars().map(cjk_digit).collect::<Option<Vec<u32>>>() {
+        return digits.iter().try_fold(0u32, |value, digit| value.checked_mul(10)?.checked_add(*digit));
+    }
+    let (tens, ones) = if let Some(ones) = text.strip_prefix('廿') {
+        (2, ones)
+    } else if let Some(ones) = text.strip_prefix('卅') {
+        (3, ones)
+    } else {
+        let (tens, ones) = text.split_once('十')?;
+        (if tens.is_empty() { 1 } else { parse_cjk_number(tens)? }, ones)
+    };
+    let ones = if ones.is_empty() { 0 } else { parse_cjk_number(ones)? };
+    if ones > 9 {
+        return None;
+    }
+    tens.checked_mul(10)?.checked_add(ones)
+}
+
+// "初一".."初十", then as a plain number
+pub(crate) fn parse_cjk_day(text: &str) -> Option<u32> {
+    match text.strip_prefix('初') {
+        Some(day) => parse_cjk_number(day).filter(|day| (1..=10).contains(day)),
+        None => parse_cjk_number(text),
+    }
+}
+
+pub(crate) fn parse_cjk_month(text: &str) -> Option<u32> {
+    match text {
+        "正" => Some(1),
+        "冬" => Some(11),
+        "腊" | "臘" => Some(12),
+        _ => parse_cjk_number(text),
+    }
+}
+
+// Folded Vietnamese month names, "Mười một" taking two words
+fn vietnamese_month(words: &[&str]) -> Option<(u32, usize)> {
+    match words {
+        ["muoi", "mot", ..] => Some((11, 2)),
+        ["muoi", "hai", ..] => Some((12, 2)),
+        [word, ..] => {
+            let month = match *word {
+                "gieng" => 1,
+                "hai" => 2,
+                "ba" => 3,
+                "tu" | "bon" => 4,
+                "nam" => 5,
+                "sau" => 6,
+                "bay" => 7,
+                "tam" => 8,
+                "chin" => 9,
+                "muoi" => 10,
+                "chap" => 12,
+                _ => word.parse().ok()?,
+            };
+            Some((month, 1))
+        }
+        [] => None,
+    }
+}
+
+impl LunarFormat {
+    // "Giáp Tý", "giap-ty", "GIÁP TÝ", "甲子", "갑자", "Jiǎ Zǐ"
+    pub fn parse_text(text: &str) -> Result<LunarFormat> {
+        let compact: String = fold(text).chars().filter(|c| !is_separator(*c)).collect();
+        pillar_from_compact(&compact).ok_or_else(|| anyhow!("{} is not a sexagenary pillar", text))
+    }
+}
+
+// A lunisolar date read from text, the year may only be known by its Can Chi
+#[derive(Clone, Debug, PartialEq)]
+pub struct ParsedLunisolarDate {
+    pub day: u32,
+    pub month: u32,
+    pub leap: bool,
+    pub year: Option<u32>,
+    pub year_pillar: Option<LunarFormat>,
+}
+
+impl ParsedLunisolarDate {
+    // "ngày 15 tháng 8 năm Quý Mão", "15/8 nhuận/2023", "Chạp 23", "mùng 1 tháng Giêng", "rằm tháng Bảy", "农历二〇二三年闰二月初一", "八月十五"
+    pub fn parse(text: &str) -> Result<ParsedLunisolarDate> {
+        let parsed = if text.chars().any(is_cjk) {
+            ParsedLunisolarDate::parse_cjk(text)?
+        } else {
+            ParsedLunisolarDate::parse_vietnamese(text)?
+        };
+        if !(1..=12).contains(&parsed.month) || !(1..=30).contains(&parsed.day) {
+            bail!("{} is out of range", text);
+        }
+        if let (Some(year), Some(pillar)) = (parsed.year, &parsed.year_pillar) {
+            if HeavenlyStem::lunar_y_from_lunisolar_y(year) != pillar.stem || EarthlyBranch::lunar_y_from_lunisolar_y(year) != pillar.branch {
+                bail!("year {} is not {}", year, pillar);
+            }
+        }
+        Ok(parsed)
+    }
+
+    fn parse_cjk(text: &str) -> Result<ParsedLunisolarDate> {
+        let compact: String = text.chars().filter(|c| !c.is_whitespace()).collect();
+        let mut rest = CALENDAR_PREFIXES.iter()
+            .find_map(|prefix| compact.strip_prefix(prefix))
+            .unwrap_or(&compact);
+        let (mut year, mut year_pillar) = (None, None);
+        if let Some((year_text, after)) = rest.split_once('年') {
+            match pillar_from_compact(year_text) {
+                Some(pillar) => year_pillar = Some(pillar),
+                None => year = Some(parse_cjk_number(year_text).ok_or_else(|| anyhow!("invalid year {}", year_text))?),
+            }
+            rest = after;
+        }
+        let leap = match rest.strip_prefix('闰').or_else(|| rest.strip_prefix('閏')) {
+            Some(after) => { rest = after; true }
+            None => false,
+        };
+        let (month_text, day_text) = rest.split_once('月').ok_or_else(|| anyhow!("no month in {}", text))?;
+        let month = parse_cjk_month(month_text).ok_or_else(|| anyhow!("invalid month {}", month_text))?;
+        let day_text = day_text.trim_end_matches(['日', '号', '號']);
+        let day = parse_cjk_day(day_text).ok_or_else(|| anyhow!("invalid day {}", day_text))?;
+        Ok(ParsedLunisolarDate { day, month, leap, year, year_pillar })
+    }
+
+    fn parse_vietnamese(text: &str) -> Result<ParsedLunisolarDate> {
+        let folded = fold(text);
+        let words: Vec<&str> = folded.split(is_separator).filter(|word| !word.is_empty()).collect();
+        let (mut day, mut month, mut year, mut year_pillar, mut leap) = (None, None, None, None, false);
+        // Bare numbers fill day, month and year in that order, "Chạp 23" having set the month first
+        let mut numbers = Vec::new();
+        let mut i = 0;
+        while i < words.len() {
+            let word = words[i];
+            let next = words.get(i + 1).copied();
+            i += match word {
+                "ngay" | "mung" | "mong" => {
+                    day = Some(next.and_then(|day| day.parse().ok()).ok_or_else(|| anyhow!("no day after {} in {}", word, text))?);
+                    2
+                }
+                "ram" => { day = Some(15); 1 }
+                "thang" => {
+                    let (value, length) = vietnamese_month(&words[i + 1..]).ok_or_else(|| anyhow!("no month after tháng in {}", text))?;
+                    month = Some(value);
+                    1 + length
+                }
+                "nam" => match next.and_then(|year| year.parse().ok()) {
+                    Some(value) => { year = Some(value); 2 }
+                    None => {
+                        let pillar = words.get(i + 1..i + 3).and_then(|pair| pillar_from_compact(&pair.concat()));
+                        year_pillar = Some(pillar.ok_or_else(|| anyhow!("no year after năm in {}", text))?);
+                        3
+                    }
+                },
+                "nhuan" => { leap = true; 1 }
+                "am" | "lich" | "al" => 1,
+                "gieng" | "chap" => {
+                    month = vietnamese_month(&words[i..]).map(|(value, _)| value);
+                    1
+                }
+                _ => match word.parse::<u32>() {
+                    Ok(number) => { numbers.push(number); 1 }
+                    Err(_) => {
+                        let pillar = next.and_then(|next| pillar_from_compact(&format!("{}{}", word, next)));
+                        year_pillar = Some(pillar.ok_or_else(|| anyhow!("unexpected \"{}\" in {}", word, text))?);
+                        2
+                    }
+                },
+            };
+        }
+        for number in numbers {
+            if day.is_none() {
+                day = Some(number);
+            } else if month.is_none() {
+                month = Some(number);
+            } else if year.is_none() {
+                year = Some(number);
+            } else {
+                bail!("too many numbers in {}", text);
+            }
+        }
+        Ok(ParsedLunisolarDate {
+            day: day.ok_or_else(|| anyhow!("no day in {}", text))?,
+            month: month.ok_or_else(|| anyhow!("no month in {}", text))?,
+            leap,
+            year,
+            year_pillar,
+        })
+    }
+
+    // The year itself, else the year with that Can Chi nearest to the reference year, else the reference year.
+    // Near either end of u32 the nearest year may not fit, then the one sixty years the other way is taken.
+    pub fn resolve_year(&self, reference_year: u32) -> u32 {
+        match (self.year, &self.year_pillar) {
+            (Some(year), _) => year,
+            (None, Some(pillar)) => {
+                let reference_year = reference_year as i64;
+                let reference_index = (reference_year + 56) % 60;
+                let offset = (pillar.sexagenary_index() as i64 + 60 - reference_index) % 60;
+                let year = if offset < 30 { reference_year + offset } else { reference_year + offset - 60 };
+                u32::try_from(year)
+                    .or_else(|_| u32::try_from(year + 60))
+                    .unwrap_or((year - 60) as u32)
+            }
+            (None, None) => reference_year,
+        }
+    }
+
+    // Fails unless the date exists in the calendar of time_zone, as NaiveLunisolarDateTime::parse_from_str
+    pub fn to_lunisolar(&self, reference_year: u32, time_zone: f64) -> Result<NaiveLunisolarDateTime> {
+        let year = self.resolve_year(reference_year);
+        let year_i32 = i32::try_from(year).map_err(|_| anyhow!("year should be between {} and {}", MIN_YEAR, MAX_YEAR))?;
+        let days_in_month = NaiveLunisolarDateTime::days_in_lunisolar_month(self.month, year_i32, self.leap, time_zone)?;
+        if self.day > days_in_month {
+            bail!("lunisolar month {}/{} has {} days", self.month, year, days_in_month);
+        }
+        Ok(NaiveLunisolarDateTime {
+            hour_lunisolar: 0,
+            minute_lunisolar: 0,
+            day_lunisolar: self.day,
+            month_lunisolar: self.month,
+            leap_lunisolar: self.leap,
+            year_lunisolar: year,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use super::*;
+
+    #[rstest]
+    #[case("Giáp Tý", 0, 0)]
+    #[case("甲子", 0, 0)]
+    #[case("gap-ty", 0, 0)]
+    #[case("Canh Thìn", 6, 4)]
+    #[case("canh thin", 6, 4)]
+    #[case("Đinh Tỵ", 3, 5)]
+    #[case("dinh ty", 3, 5)]
+    #[case("QUÝ MÃO", 9, 3)]
+    #[case("癸卯", 9, 3)]
+    #[case("계묘", 9, 3)]
+    #[case("Guǐ Mǎo", 9, 3)]
+    fn test_parse_pillar(#[case] text: &str, #[case] stem: u32, #[case] branch: u32) {
+        assert_eq!(LunarFormat::parse_text(text).unwrap(), LunarFormat::new(HeavenlyStem::from_numeric(stem), EarthlyBranch::from_numeric(branch)));
+    }
+
+    #[test]
+    fn test_parse_pillar_error() {
+        assert!(LunarFormat::parse_text("Giáp Sửu").is_err());
+        assert!(LunarFormat::parse_text("Giáp").is_err());
+        assert!(LunarFormat::parse_text("").is_err());
+    }
+
+    #[rstest]
+    #[case("ngày 15 tháng 8 năm Quý Mão", 15, 8, false, None)]
+    #[case("15/8 nhuận/2023", 15, 8, true, Some(2023))]
+    #[case("Chạp 23", 23, 12, false, None)]
+    #[case("ngày 1 tháng Giêng năm 2024", 1, 1, false, Some(2024))]
+    #[case("ngay 10 thang muoi mot", 10, 11, false, None)]
+    #[case("mùng 1 tháng Giêng", 1, 1, false, None)]
+    #[case("rằm tháng Bảy năm 2024", 15, 7, false, Some(2024))]
+    #[case("农历二〇二三年闰二月初一", 1, 2, true, Some(2023))]
+    #[case("八月十五", 15, 8, false, None)]
+    #[case("臘月廿三", 23, 12, false, None)]
+    #[case("癸卯年正月三十", 30, 1, false, None)]
+    fn test_parse_date(#[case] text: &str, #[case] day: u32, #[case] month: u32, #[case] leap: bool, #[case] year: Option<u32>) {
+        let parsed = ParsedLunisolarDate::parse(text).unwrap();
+        assert_eq!((parsed.day, parsed.month, parsed.leap, parsed.year), (day, month, leap, year));
+    }
+
+    #[test]
+    fn test_resolve_year() {
+        let parsed = ParsedLunisolarDate::parse("ngày 15 tháng 8 năm Quý Mão").unwrap();
+        assert_eq!(parsed.year_pillar, Some(LunarFormat::new(HeavenlyStem::HS10, EarthlyBranch::EB4)));
+        assert_eq!(parsed.resolve_year(2024), 2023);
+        assert_eq!(parsed.resolve_year(2060), 2083);
+        assert_eq!(parsed.to_lunisolar(2024, 7.0).unwrap().year_lunisolar, 2023);
+        assert_eq!(ParsedLunisolarDate::parse("Chạp 23").unwrap().resolve_year(2024), 2024);
+        assert_eq!(parsed.resolve_year(10), 43);
+        assert_eq!(parsed.resolve_year(u32::MAX), u32::MAX - 32);
+
+        assert!(ParsedLunisolarDate::parse("15/2 nhuận/2024").unwrap().to_lunisolar(2024, 7.0).is_err());
+        assert!(ParsedLunisolarDate::parse("30/12/2024").unwrap().to_lunisolar(2024, 7.0).is_err());
+        assert!(ParsedLunisolarDate::parse("30/12/2023").unwrap().to_lunisolar(2024, 7.0).is_ok());
+        assert!(parsed.to_lunisolar(10, 7.0).is_err());
+
+        assert!(ParsedLunisolarDate::parse("năm 2024 Quý Mão ngày 1 tháng 1").is_err());
+        assert!(ParsedLunisolarDate::parse("ngày 31 tháng 1").is_err());
+        assert!(ParsedLunisolarDate::parse("hello").is_err());
+    }
+
+    #[test]
+    fn test_parse_cjk_number() {
+        assert_eq!(parse_cjk_number("二〇二三"), Some(2023));
+        assert_eq!(parse_cjk_number("十"), Some(10));
+        assert_eq!(parse_cjk_number("十五"), Some(15));
+        assert_eq!(parse_cjk_number("二十"), Some(20));
+        assert_eq!(parse_cjk_number("廿九"), Some(29));
+        assert_eq!(parse_cjk_number("卅"), Some(30));
+        assert_eq!(parse_cjk_day("初十"), Some(10));
+        assert_eq!(parse_cjk_day("初十一"), None);
+        assert_eq!(parse_cjk_number("二〇二三二〇二三二〇二三"), None);
+        assert_eq!(parse_cjk_number("四二九四九六七二九五十"), None);
+        assert!(ParsedLunisolarDate::parse("二〇二三二〇二三二〇二三年正月初一").is_err());
+    }
+}
@@ -0,0 +1,145 @@
+use chrono::NaiveTime;
+use super::lunar_datetime::*;
+use super::zodiac_and_element::*;
+
+// Thập nhị thần, in the order they follow each other through the branches
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum TwelveSpirit {
+    ThanhLong,
+    MinhDuong,
+    ThienHinh,
+    ChuTuoc,
+    KimQuy,
+    KimDuong,
+    BachHo,
+    NgocDuong,
+    ThienLao,
+    HuyenVu,
+    TuMenh,
+    CauTran,
+}
+
+impl TwelveSpirit {
+    pub fn from_numeric(num: u32) -> TwelveSpirit {
+        match num % 12 {
+            0 => TwelveSpirit::ThanhLong,
+            1 => TwelveSpirit::MinhDuong,
+            2 => TwelveSpirit::ThienHinh,
+            3 => TwelveSpirit::ChuTuoc,
+            4 => TwelveSpirit::KimQuy,
+            5 => TwelveSpirit::KimDuong,
+            6 => TwelveSpirit::BachHo,
+            7 => TwelveSpirit::NgocDuong,
+            8 => TwelveSpirit::ThienLao,
+            9 => TwelveSpirit::HuyenVu,
+            10 => TwelveSpirit::TuMenh,
+            _ => TwelveSpirit::CauTran,
+        }
+    }
+
+    // Hoàng đạo spirits are the good ones, the other six are Hắc đạo
+    pub fn is_hoang_dao(&self) -> bool {
+        matches!(self,
+            TwelveSpirit::ThanhLong | TwelveSpirit::MinhDuong | TwelveSpirit::KimQuy |
+            TwelveSpirit::KimDuong | TwelveSpirit::NgocDuong | TwelveSpirit::TuMenh)
+    }
+
+    pub fn fortune(&self) -> Fortune {
+        if self.is_hoang_dao() { Fortune::Good } else { Fortune::Bad }
+    }
+
+    // Thanh Long starts on Tý for Dần/Thân, on Dần for Mão/Dậu, ... and on Tuất for Sửu/Mùi
+    pub fn from_branches(anchor: &EarthlyBranch, target: &EarthlyBranch) -> TwelveSpirit {
+        let start = (anchor.to_numeric() + 10) % 6 * 2;
+        TwelveSpirit::from_numeric(target.to_numeric() + 12 - start)
+    }
+
+    // Days are classified from the lunar month branch
+    pub fn of_day(date: &NaiveLunarDateTime) -> TwelveSpirit {
+        TwelveSpirit::from_branches(&date.month_lunar.branch, &date.date_lunar.branch)
+    }
+
+    // Hours are classified from the day branch, using the same rotation as days
+    pub fn of_hour(date: &NaiveLunarDateTime) -> TwelveSpirit {
+        TwelveSpirit::from_branches(&date.date_lunar.branch, &date.hour_lunar.branch)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AuspiciousHour {
+    pub branch: EarthlyBranch,
+    pub spirit: TwelveSpirit,
+    pub start: NaiveTime,
+    pub end: NaiveTime,
+}
+
+impl AuspiciousHour {
+    // Tý covers 23:00 - 01:00, Sửu 01:00 - 03:00 and so on
+    pub fn new(branch: EarthlyBranch, spirit: TwelveSpirit) -> AuspiciousHour {
+        let start_hour = (branch.to_numeric() * 2 + 23) % 24;
+        AuspiciousHour {
+            start: NaiveTime::from_hms_opt(start_hour, 0, 0).unwrap(),
+            end: NaiveTime::from_hms_opt((start_hour + 2) % 24, 0, 0).unwrap(),
+            branch,
+            spirit,
+        }
+    }
+}
+
+// The six Hoàng đạo hours of the day, starting from Tý
+pub fn auspicious_hours(date: &NaiveLunarDateTime) -> Vec<AuspiciousHour> {
+    (0..12)
+        .map(EarthlyBranch::from_numeric)
+        .map(|branch| {
+            let spirit = TwelveSpirit::from_branches(&date.date_lunar.branch, &branch);
+            AuspiciousHour::new(branch, spirit)
+        })
+        .filter(|hour| hour.spirit.is_hoang_dao())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use rstest::rstest;
+    use chrono::{DateTime, FixedOffset, NaiveDate};
+    use super::*;
+
+    fn lunar_datetime(yyyy: i32, mm: u32, dd: u32, hour: u32) -> NaiveLunarDateTime {
+        let offset = FixedOffset::east_opt(7 * 3600).unwrap();
+        let naive = NaiveDate::from_ymd_opt(yyyy, mm, dd).unwrap().and_hms_opt(hour, 0, 0).unwrap();
+        let datetime: DateTime<FixedOffset> = naive.and_local_timezone(offset).unwrap();
+        NaiveLunarDateTime::new_from_datetime(datetime).unwrap()
+    }
+
+    #[rstest]
+    #[case(EarthlyBranch::EB_TIGER, EarthlyBranch::EB_RAT, TwelveSpirit::ThanhLong)]
+    #[case(EarthlyBranch::EB_MONKEY, EarthlyBranch::EB_BUFFALO, TwelveSpirit::MinhDuong)]
+    #[case(EarthlyBranch::EB_CAT, EarthlyBranch::EB_TIGER, TwelveSpirit::ThanhLong)]
+    #[case(EarthlyBranch::EB_BUFFALO, EarthlyBranch::EB_DOG, TwelveSpirit::ThanhLong)]
+    #[case(EarthlyBranch::EB_RAT, EarthlyBranch::EB_RAT, TwelveSpirit::KimQuy)]
+    fn test_from_branches(#[case] anchor: EarthlyBranch, #[case] target: EarthlyBranch, #[case] expected: TwelveSpirit) {
+        assert_eq!(TwelveSpirit::from_branches(&anchor, &target), expected);
+    }
+
+    #[test]
+    fn test_auspicious_hours() {
+        // 1996-04-21 is a Tý day: Tý, Sửu, Mão, Ngọ, Thân and Dậu are Hoàng đạo
+        let hours = auspicious_hours(&lunar_datetime(1996, 4, 21, 10));
+        let branches: Vec<EarthlyBranch> = hours.iter().map(|h| h.branch).collect();
+        assert_eq!(branches, vec![EarthlyBranch::EB_RAT, EarthlyBranch::EB_BUFFALO, EarthlyBranch::EB_CAT,
+                                  EarthlyBranch::EB_HORSE, EarthlyBranch::EB_MONKEY, EarthlyBranch::EB_CHICKEN]);
+        assert_eq!(hours[0].start, NaiveTime::from_hms_opt(23, 0, 0).unwrap());
+        assert_eq!(hours[0].end, NaiveTime::from_hms_opt(1, 0, 0).unwrap());
+        assert_eq!(hours[5].start, NaiveTime::from_hms_opt(17, 0, 0).unwrap());
+    }
+
+    #[test]
+    fn test_of_day_and_hour() {
+        // 1996-04-21 is day Mậu Tý in lunar month 3 (Thìn), Thanh Long falls on Thìn days
+        let date = lunar_datetime(1996, 4, 21, 10);
+        assert_eq!(TwelveSpirit::of_day(&date), TwelveSpirit::ThienLao);
+        assert_eq!(TwelveSpirit::of_day(&date).fortune(), Fortune::Bad);
+        // 10:00 is the Tỵ hour, Thanh Long hour on a Tý day is Thân
+        assert_eq!(TwelveSpirit::of_hour(&date), TwelveSpirit::HuyenVu);
+    }
+}
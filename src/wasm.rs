@@ -0,0 +1,313 @@
+// JavaScript API of the wasm build: `wasm-pack build --features wasm` emits the module and its .d.ts.
+// Dates cross the boundary as plain numbers in the local time of an explicit time_zone (hours east
+// of UTC) rather than as JS Date, so the browser's own time zone never leaks into the results.
+use anyhow::{anyhow, Result};
+use chrono::{Datelike, DateTime, FixedOffset, NaiveDate, TimeZone};
+use wasm_bindgen::prelude::*;
+use super::lunar_datetime::*;
+use super::lunisolar_datetime::*;
+use super::localization::*;
+use super::solar_term::*;
+use super::calendar_system::*;
+use super::holiday::*;
+
+#[wasm_bindgen(typescript_custom_section)]
+const TS_TYPES: &'static str = r#"
+export type Locale = "vi" | "zh-Hans" | "zh-Hant" | "ko" | "ja" | "en";
+export type CalendarSystemName = "vietnamese" | "chinese" | "korean" | "japanese";
+"#;
+
+#[wasm_bindgen]
+extern "C" {
+    #[wasm_bindgen(typescript_type = "Locale")]
+    pub type LocaleTag;
+    #[wasm_bindgen(typescript_type = "CalendarSystemName")]
+    pub type CalendarSystemName;
+}
+
+fn js_error(error: anyhow::Error) -> JsError {
+    JsError::new(&error.to_string())
+}
+
+fn locale(tag: &LocaleTag) -> Result<Locale> {
+    let tag = tag.as_string().ok_or_else(|| anyhow!("locale should be a string"))?;
+    Locale::from_tag(&tag).ok_or_else(|| anyhow!("unknown locale {}", tag))
+}
+
+fn calendar_system(name: &CalendarSystemName) -> Result<CalendarSystem> {
+    match name.as_string().unwrap_or_default().as_str() {
+        "vietnamese" => Ok(CalendarSystem::Vietnamese),
+        "chinese" => Ok(CalendarSystem::Chinese),
+        "korean" => Ok(CalendarSystem::Korean),
+        "japanese" => Ok(CalendarSystem::Japanese),
+        other => Err(anyhow!("unknown calendar system {}", other)),
+    }
+}
+
+// NaN would otherwise round to an offset of 0 and silently compute in UTC
+fn offset(time_zone: f64) -> Result<FixedOffset> {
+    if !time_zone.is_finite() {
+        return Err(anyhow!("invalid time zone {}", time_zone));
+    }
+    FixedOffset::east_opt((time_zone * 3600.0).round() as i32).ok_or_else(|| anyhow!("invalid time zone {}", time_zone))
+}
+
+fn check_year(year: i32) -> Result<()> {
+    if !(MIN_YEAR..=MAX_YEAR).contains(&year) {
+        return Err(anyhow!("year should be between {} and {}", MIN_YEAR, MAX_YEAR));
+    }
+    Ok(())
+}
+
+fn datetime(year: i32, month: u32, day: u32, hour: u32, minute: u32, time_zone: f64) -> Result<DateTime<FixedOffset>> {
+    let offset = offset(time_zone)?;
+    let naive = NaiveDate::from_ymd_opt(year, month, day)
+        .and_then(|date| date.and_hms_opt(hour, minute, 0))
+        .ok_or_else(|| anyhow!("invalid date {}-{}-{} {}:{}", year, month, day, hour, minute))?;
+    offset.from_local_datetime(&naive).single().ok_or_else(|| anyhow!("invalid local time {}", naive))
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct GregorianDate {
+    #[wasm_bindgen(readonly)]
+    pub year: i32,
+    #[wasm_bindgen(readonly)]
+    pub month: u32,
+    #[wasm_bindgen(readonly)]
+    pub day: u32,
+}
+
+impl From<NaiveDate> for GregorianDate {
+    fn from(date: NaiveDate) -> GregorianDate {
+        GregorianDate { year: date.year(), month: date.month(), day: date.day() }
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LunisolarDate {
+    #[wasm_bindgen(readonly)]
+    pub year: u32,
+    #[wasm_bindgen(readonly)]
+    pub month: u32,
+    #[wasm_bindgen(readonly)]
+    pub day: u32,
+    #[wasm_bindgen(readonly)]
+    pub leap: bool,
+    #[wasm_bindgen(readonly)]
+    pub hour: u32,
+    #[wasm_bindgen(readonly)]
+    pub minute: u32,
+}
+
+impl From<NaiveLunisolarDateTime> for LunisolarDate {
+    fn from(lunisolar: NaiveLunisolarDateTime) -> LunisolarDate {
+        LunisolarDate {
+            year: lunisolar.year_lunisolar,
+            month: lunisolar.month_lunisolar,
+            day: lunisolar.day_lunisolar,
+            leap: lunisolar.leap_lunisolar,
+            hour: lunisolar.hour_lunisolar,
+            minute: lunisolar.minute_lunisolar,
+        }
+    }
+}
+
+// Stem 0 (Giáp) to 9 (Quý) and branch 0 (Tý) to 11 (Hợi)
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pillar {
+    #[wasm_bindgen(readonly)]
+    pub stem: u8,
+    #[wasm_bindgen(readonly)]
+    pub branch: u8,
+}
+
+impl From<LunarFormat> for Pillar {
+    fn from(pillar: LunarFormat) -> Pillar {
+        Pillar { stem: pillar.stem.to_numeric() as u8, branch: pillar.branch.to_numeric() as u8 }
+    }
+}
+
+impl Pillar {
+    fn localized_name(&self, locale: &Locale) -> String {
+        let stem = HeavenlyStem::from_numeric(self.stem as u32).localized_name(locale);
+        let branch = EarthlyBranch::from_numeric(self.branch as u32).localized_name(locale);
+        match locale {
+            Locale::Vi | Locale::En => format!("{} {}", stem, branch),
+            _ => format!("{}{}", stem, branch),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Pillar {
+    // "Giáp Thìn", "甲辰", ...
+    pub fn name(&self, locale: &LocaleTag) -> Result<String, JsError> {
+        Ok(self.localized_name(&self::locale(locale).map_err(js_error)?))
+    }
+}
+
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Pillars {
+    #[wasm_bindgen(readonly)]
+    pub year: Pillar,
+    #[wasm_bindgen(readonly)]
+    pub month: Pillar,
+    #[wasm_bindgen(readonly)]
+    pub day: Pillar,
+    #[wasm_bindgen(readonly)]
+    pub hour: Pillar,
+}
+
+// Term index 0 is Xuân phân, at 0° of solar longitude
+#[wasm_bindgen]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SolarTermDate {
+    #[wasm_bindgen(readonly)]
+    pub term: u32,
+    #[wasm_bindgen(readonly)]
+    pub longitude: u32,
+    #[wasm_bindgen(readonly)]
+    pub date: GregorianDate,
+}
+
+#[wasm_bindgen]
+impl SolarTermDate {
+    pub fn name(&self, locale: &LocaleTag) -> Result<String, JsError> {
+        Ok(SolarTerm::from_numeric(self.term).localized_name(&self::locale(locale).map_err(js_error)?).to_string())
+    }
+}
+
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct HolidayDate {
+    #[wasm_bindgen(readonly)]
+    pub name: String,
+    #[wasm_bindgen(readonly)]
+    pub date: GregorianDate,
+}
+
+fn lunisolar_of(year: i32, month: u32, day: u32, hour: u32, minute: u32, time_zone: f64) -> Result<LunisolarDate> {
+    Ok(NaiveLunisolarDateTime::new_from_datetime(datetime(year, month, day, hour, minute, time_zone)?)?.into())
+}
+
+fn pillars_of(year: i32, month: u32, day: u32, hour: u32, minute: u32, time_zone: f64) -> Result<Pillars> {
+    let lunar = NaiveLunarDateTime::new_from_datetime(datetime(year, month, day, hour, minute, time_zone)?)?;
+    Ok(Pillars {
+        year: lunar.year_lunar.into(),
+        month: lunar.month_lunar.into(),
+        day: lunar.date_lunar.into(),
+        hour: lunar.hour_lunar.into(),
+    })
+}
+
+fn solar_terms_of(year: i32, time_zone: f64) -> Result<Vec<SolarTermDate>> {
+    offset(time_zone)?;
+    Ok(SolarTerm::dates_in_year(year, time_zone)?.into_iter()
+        .map(|(term, date)| SolarTermDate { term: term.to_numeric(), longitude: term.longitude(), date: date.into() })
+        .collect())
+}
+
+fn leap_month_of(year: i32, time_zone: f64) -> Result<Option<u32>> {
+    check_year(year)?;
+    offset(time_zone)?;
+    Ok(NaiveLunisolarDateTime::leap_month_of_year(year, time_zone))
+}
+
+fn gregorian_of(year: i32, month: u32, day: u32, leap: bool, time_zone: f64) -> Result<GregorianDate> {
+    offset(time_zone)?;
+    Ok(NaiveLunisolarDateTime::lunisolar_to_gregorian(day, month, year, leap, time_zone)?.into())
+}
+
+fn holidays_of(year: i32, system: &CalendarSystem) -> Result<Vec<HolidayDate>> {
+    Ok(festivals_in_year(system, year)?.into_iter()
+        .map(|occurrence| HolidayDate { name: occurrence.holiday.name.to_string(), date: occurrence.date.into() })
+        .collect())
+}
+
+#[wasm_bindgen(js_name = gregorianToLunisolar)]
+pub fn gregorian_to_lunisolar(year: i32, month: u32, day: u32, hour: u32, minute: u32, time_zone: f64) -> Result<LunisolarDate, JsError> {
+    lunisolar_of(year, month, day, hour, minute, time_zone).map_err(js_error)
+}
+
+#[wasm_bindgen(js_name = lunisolarToGregorian)]
+pub fn lunisolar_to_gregorian(year: i32, month: u32, day: u32, leap: bool, time_zone: f64) -> Result<GregorianDate, JsError> {
+    gregorian_of(year, month, day, leap, time_zone).map_err(js_error)
+}
+
+#[wasm_bindgen(js_name = leapMonth)]
+pub fn leap_month(year: i32, time_zone: f64) -> Result<Option<u32>, JsError> {
+    leap_month_of(year, time_zone).map_err(js_error)
+}
+
+#[wasm_bindgen]
+pub fn pillars(year: i32, month: u32, day: u32, hour: u32, minute: u32, time_zone: f64) -> Result<Pillars, JsError> {
+    pillars_of(year, month, day, hour, minute, time_zone).map_err(js_error)
+}
+
+#[wasm_bindgen(js_name = solarTerms)]
+pub fn solar_terms(year: i32, time_zone: f64) -> Result<Vec<SolarTermDate>, JsError> {
+    solar_terms_of(year, time_zone).map_err(js_error)
+}
+
+// Festivals of a tradition in a gregorian year, reckoned at the tradition's own meridian
+#[wasm_bindgen]
+pub fn holidays(year: i32, system: &CalendarSystemName) -> Result<Vec<HolidayDate>, JsError> {
+    holidays_of(year, &calendar_system(system).map_err(js_error)?).map_err(js_error)
+}
+
+#[wasm_bindgen(js_name = stemName)]
+pub fn stem_name(stem: u8, locale: &LocaleTag) -> Result<String, JsError> {
+    let stem = HeavenlyStem::try_from(stem).map_err(js_error)?;
+    Ok(stem.localized_name(&self::locale(locale).map_err(js_error)?).to_string())
+}
+
+#[wasm_bindgen(js_name = branchName)]
+pub fn branch_name(branch: u8, locale: &LocaleTag) -> Result<String, JsError> {
+    let branch = EarthlyBranch::try_from(branch).map_err(js_error)?;
+    Ok(branch.localized_name(&self::locale(locale).map_err(js_error)?).to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // JS values only exist on wasm32, so the tests go through the plain Rust halves
+    #[test]
+    fn test_conversions() {
+        let lunisolar = lunisolar_of(2023, 3, 31, 10, 30, 7.0).unwrap();
+        assert_eq!((lunisolar.year, lunisolar.month, lunisolar.day, lunisolar.leap), (2023, 2, 10, true));
+        assert!(lunisolar_of(2023, 2, 30, 0, 0, 7.0).is_err());
+        assert!(lunisolar_of(1800, 1, 1, 0, 0, 7.0).is_err());
+        assert!(lunisolar_of(2023, 3, 31, 10, 30, f64::NAN).is_err());
+        assert!(lunisolar_of(2023, 3, 31, 10, 30, f64::INFINITY).is_err());
+        assert_eq!(gregorian_of(2023, 2, 10, true, 7.0).unwrap(), GregorianDate { year: 2023, month: 3, day: 31 });
+        assert!(gregorian_of(2023, 2, 10, true, f64::NAN).is_err());
+
+        assert_eq!(leap_month_of(2023, 7.0).unwrap(), Some(2));
+        assert_eq!(leap_month_of(2024, 7.0).unwrap(), None);
+        assert!(leap_month_of(2200, 7.0).is_err());
+        assert!(leap_month_of(2023, f64::NAN).is_err());
+
+        let pillars = pillars_of(2024, 2, 10, 10, 30, 7.0).unwrap();
+        assert_eq!(pillars.year, Pillar { stem: 0, branch: 4 });
+        assert_eq!(pillars.year.localized_name(&Locale::Vi), "Giáp Thìn");
+        assert_eq!(pillars.year.localized_name(&Locale::ZhHant), "甲辰");
+    }
+
+    #[test]
+    fn test_terms_and_holidays() {
+        let terms = solar_terms_of(2024, 7.0).unwrap();
+        assert_eq!(terms.len(), 24);
+        assert_eq!(terms[2], SolarTermDate { term: 21, longitude: 315, date: GregorianDate { year: 2024, month: 2, day: 4 } });
+        assert!(solar_terms_of(2200, 7.0).is_err());
+        assert!(solar_terms_of(2024, f64::NAN).is_err());
+
+        let holidays = holidays_of(2024, &CalendarSystem::Vietnamese).unwrap();
+        let tet = holidays.iter().find(|holiday| holiday.name == "Tết Nguyên Đán").unwrap();
+        assert_eq!(tet.date, GregorianDate { year: 2024, month: 2, day: 10 });
+    }
+}
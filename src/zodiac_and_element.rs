@@ -1,6 +1,8 @@
 use super::lunar_datetime::*;
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Zodiac {
     Rat,
     Buffalo,
@@ -14,28 +16,63 @@ pub enum Zodiac {
     Chicken,
     Dog,
     Pig,
+    // Animals other traditions put on the same branches
+    Ox,
+    Rabbit,
+    Sheep,
+    Rooster,
+    Boar,
+    Naga,
 }
 
 impl Zodiac {
-    pub fn as_zodiac(&self) -> EarthlyBranch {
-        match self {
-            Zodiac::Rat => { EarthlyBranch::EB1 }
-            Zodiac::Buffalo => { EarthlyBranch::EB2 }
-            Zodiac::Tiger => { EarthlyBranch::EB3 }
-            Zodiac::Cat => { EarthlyBranch::EB4 }
-            Zodiac::Dragon => { EarthlyBranch::EB5 }
-            Zodiac::Snake => { EarthlyBranch::EB6 }
-            Zodiac::Horse => { EarthlyBranch::EB7 }
-            Zodiac::Goat => { EarthlyBranch::EB8 }
-            Zodiac::Monkey => { EarthlyBranch::EB9 }
-            Zodiac::Chicken => { EarthlyBranch::EB10 }
-            Zodiac::Dog => { EarthlyBranch::EB11 }
-            Zodiac::Pig => { EarthlyBranch::EB12 }
+    pub const ALL: [Zodiac; 18] = [
+        Zodiac::Rat, Zodiac::Buffalo, Zodiac::Tiger, Zodiac::Cat, Zodiac::Dragon, Zodiac::Snake,
+        Zodiac::Horse, Zodiac::Goat, Zodiac::Monkey, Zodiac::Chicken, Zodiac::Dog, Zodiac::Pig,
+        Zodiac::Ox, Zodiac::Rabbit, Zodiac::Sheep, Zodiac::Rooster, Zodiac::Boar, Zodiac::Naga,
+    ];
+}
+
+// The Vietnamese animal of a branch, see EarthlyBranch::as_zodiac_in for other traditions
+impl From<EarthlyBranch> for Zodiac {
+    fn from(branch: EarthlyBranch) -> Zodiac {
+        Zodiac::ALL[branch.to_numeric() as usize]
+    }
+}
+
+// Animals of every tradition map back to their branch
+impl From<Zodiac> for EarthlyBranch {
+    fn from(zodiac: Zodiac) -> EarthlyBranch {
+        match zodiac {
+            Zodiac::Ox => { EarthlyBranch::EB2 }
+            Zodiac::Rabbit => { EarthlyBranch::EB4 }
+            Zodiac::Sheep => { EarthlyBranch::EB8 }
+            Zodiac::Rooster => { EarthlyBranch::EB10 }
+            Zodiac::Boar => { EarthlyBranch::EB12 }
+            Zodiac::Naga => { EarthlyBranch::EB5 }
+            _ => { EarthlyBranch::from_numeric(zodiac as u32) }
         }
     }
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+// Which animal each tradition puts on a branch, the branches themselves are shared
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum ZodiacTradition {
+    // Buffalo, Cat, Goat, Chicken, Pig
+    Vietnamese,
+    // Ox, Rabbit, Goat, Rooster, Pig
+    Chinese,
+    // Ox, Rabbit, Sheep, Rooster, Boar
+    Japanese,
+    // Ox, Rabbit, Sheep, Rooster, Pig
+    Korean,
+    // Ox, Rabbit, Naga, Goat, Rooster, Pig
+    Thai,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum Element {
     Metal,
     Wood,
@@ -44,7 +81,40 @@ pub enum Element {
     Earth,
 }
 
-#[derive(Clone, Debug, PartialEq, Eq)]
+impl Element {
+    // Tương sinh: Wood feeds Fire, Fire makes Earth, Earth bears Metal, Metal carries Water, Water nourishes Wood
+    pub fn generates(&self) -> Element {
+        match self {
+            Element::Wood => { Element::Fire }
+            Element::Fire => { Element::Earth }
+            Element::Earth => { Element::Metal }
+            Element::Metal => { Element::Water }
+            Element::Water => { Element::Wood }
+        }
+    }
+
+    // Tương khắc: Wood parts Earth, Earth dams Water, Water quenches Fire, Fire melts Metal, Metal chops Wood
+    pub fn overcomes(&self) -> Element {
+        match self {
+            Element::Wood => { Element::Earth }
+            Element::Earth => { Element::Water }
+            Element::Water => { Element::Fire }
+            Element::Fire => { Element::Metal }
+            Element::Metal => { Element::Wood }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Fortune {
+    Good,
+    Neutral,
+    Bad,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "lowercase"))]
 pub enum YinYang {
     Yin,
     // negative